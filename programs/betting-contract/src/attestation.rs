@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::error::BettingError;
+
+/// Size of the header before the signature offsets: signature count and padding
+const HEADER_LEN: usize = 2;
+
+/// Size of one `Ed25519SignatureOffsets` entry, seven little-endian u16s
+const OFFSETS_LEN: usize = 14;
+
+/// Instruction index meaning "this instruction's own data"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// The message a game server signs to attest a result: `pool || outcome || attested_at (LE)`
+pub fn attestation_message(betting_pool: &Pubkey, winning_outcome: u8, attested_at: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 1 + 8);
+    message.extend_from_slice(betting_pool.as_ref());
+    message.push(winning_outcome);
+    message.extend_from_slice(&attested_at.to_le_bytes());
+    message
+}
+
+/// Check that the instruction just before the current one is an ed25519 program instruction
+/// verifying exactly one signature by `signer` over `message`.
+///
+/// The ed25519 program fails the whole transaction on a bad signature, so all that is left
+/// is to make sure it checked the key and message we expect.
+pub fn verify_ed25519_signature(instructions_sysvar: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, BettingError::InvalidAttestation);
+    let instruction = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+
+    require_keys_eq!(instruction.program_id, ed25519_program::ID, BettingError::InvalidAttestation);
+    require!(instruction.accounts.is_empty(), BettingError::InvalidAttestation);
+
+    let data = &instruction.data;
+    require!(data.len() >= HEADER_LEN + OFFSETS_LEN, BettingError::InvalidAttestation);
+    require!(data[0] == 1, BettingError::InvalidAttestation);
+
+    let offsets = &data[HEADER_LEN..HEADER_LEN + OFFSETS_LEN];
+    let read_u16 = |index: usize| u16::from_le_bytes([offsets[index * 2], offsets[index * 2 + 1]]);
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = read_u16(2) as usize;
+    let public_key_instruction_index = read_u16(3);
+    let message_data_offset = read_u16(4) as usize;
+    let message_data_size = read_u16(5) as usize;
+    let message_instruction_index = read_u16(6);
+
+    // Everything must live in the ed25519 instruction itself, not in some other instruction
+    require!(
+        signature_instruction_index == CURRENT_INSTRUCTION
+            && public_key_instruction_index == CURRENT_INSTRUCTION
+            && message_instruction_index == CURRENT_INSTRUCTION,
+        BettingError::InvalidAttestation
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(BettingError::InvalidAttestation)?;
+    require!(public_key == signer.as_ref(), BettingError::InvalidAttestation);
+
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(BettingError::InvalidAttestation)?;
+    require!(signed_message == message, BettingError::InvalidAttestation);
+
+    Ok(())
+}
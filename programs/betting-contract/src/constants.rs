@@ -3,5 +3,34 @@ use anchor_lang::prelude::*;
 #[constant]
 pub const SEED: &str = "anchor";
 
-pub static PLATFORM_WALLET: Pubkey = pubkey!("HHEvS6uKozLLA4HkV8pujd7AKBDX9rbjWLWerxGc8swc");
+/// Minimum number of outcomes a pool can be created with
+#[constant]
+pub const MIN_OUTCOMES: u8 = 2;
+
+/// Maximum number of outcomes a pool can be created with
+#[constant]
+pub const MAX_OUTCOMES: u8 = 16;
+
+/// Maximum number of keys in a pool's moderator set
+#[constant]
+pub const MAX_MODERATORS: u8 = 5;
+
+/// Maximum length of an outcome label
+pub const MAX_OUTCOME_LABEL_LEN: usize = 32;
+
+/// Winning outcome that voids the pool (every bet is refunded)
+#[constant]
+pub const OUTCOME_VOID: u8 = 0;
+
+/// Winning outcome for a drawn match (every bet is refunded)
+#[constant]
+pub const OUTCOME_DRAW: u8 = 255;
 
+/// Highest combined creator and platform fee, in basis points (50%)
+#[constant]
+pub const MAX_FEE_RATE: u16 = 5000;
+
+
+/// Fixed-point scale of fixed odds: 25_000 is decimal odds of 2.5
+#[constant]
+pub const ODDS_PRECISION: u32 = 10_000;
@@ -5,7 +5,7 @@ pub enum BettingError {
     #[msg("Betting is closed for this pool")]
     BettingClosed,
     
-    #[msg("Invalid betting prediction. Must be 1 (Player 1) or 2 (Player 2)")]
+    #[msg("Invalid betting prediction. Must be one of the pool's outcomes")]
     InvalidPrediction,
     
     #[msg("Insufficient funds for bet")]
@@ -20,7 +20,7 @@ pub enum BettingError {
     #[msg("Only the admin can declare winners")]
     UnauthorizedAdmin,
     
-    #[msg("Invalid winning outcome. Must be 1 (Player 1) or 2 (Player 2)")]
+    #[msg("Invalid winning outcome. Must be 0 (Void), one of the pool's outcomes or 255 (Draw)")]
     InvalidWinningOutcome,
     
     #[msg("Payouts have already been completed")]
@@ -38,7 +38,7 @@ pub enum BettingError {
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
     
-    #[msg("No bets placed on winning outcome")]
+    #[msg("No bets placed on winning outcome. Declare the pool void instead")]
     NoBetsOnWinningOutcome,
     
     #[msg("Invalid betting pool for this bet")]
@@ -52,4 +52,175 @@ pub enum BettingError {
     
     #[msg("Nothing to payout")]
     NothingToPayout,
+    
+    #[msg("This betting pool has been cancelled")]
+    PoolCancelled,
+    
+    #[msg("Refunds are only available for cancelled, void or drawn pools")]
+    RefundsNotAvailable,
+    
+    #[msg("A pool needs between 2 and 16 outcomes")]
+    InvalidOutcomeCount,
+    
+    #[msg("Outcome label is too long (max 32 characters)")]
+    OutcomeLabelTooLong,
+    
+    #[msg("Mint does not match the betting pool's mint")]
+    InvalidMint,
+    
+    #[msg("Token pools require the mint, token vault, token account and token program")]
+    MissingTokenAccounts,
+    
+    #[msg("Token account does not belong to the recipient")]
+    InvalidTokenAccount,
+    
+    #[msg("This fee has already been paid")]
+    FeeAlreadyPaid,
+    
+    #[msg("Only the platform authority can change the program config")]
+    UnauthorizedConfigAuthority,
+    
+    #[msg("Invalid fee rate")]
+    InvalidFeeRate,
+    
+    #[msg("Invalid bet limits. Minimum must be above zero and at most the maximum")]
+    InvalidBetLimits,
+    
+    #[msg("Bet is below the minimum bet")]
+    BetTooSmall,
+    
+    #[msg("Bet is above the maximum bet")]
+    BetTooLarge,
+    
+    #[msg("The program is paused")]
+    ProgramPaused,
+    
+    #[msg("Winning bets are closed by claiming them with payout_winners")]
+    BetIsWinner,
+    
+    #[msg("The pool still has unpaid winnings, refunds or fees")]
+    SettlementIncomplete,
+    
+    #[msg("The pool still has open positions")]
+    PositionsStillOpen,
+    
+    #[msg("Batch accounts must be (position, winner) pairs, plus the winner's token account for token pools")]
+    InvalidBatchAccounts,
+    
+    #[msg("The pool cannot move from its current status to the requested one")]
+    InvalidStatusTransition,
+    
+    #[msg("The pool's status does not allow this instruction")]
+    InvalidPoolStatus,
+    
+    #[msg("Betting must be locked before a result can be declared")]
+    PoolNotLocked,
+    
+    #[msg("The minimum delay after locking has not passed yet")]
+    ResolutionTooEarly,
+    
+    #[msg("Invalid resolution delay")]
+    InvalidResolutionDelay,
+    
+    #[msg("Invalid dispute window")]
+    InvalidDisputeWindow,
+    
+    #[msg("Payouts open once the dispute window closes")]
+    DisputeWindowOpen,
+    
+    #[msg("The dispute window has closed")]
+    DisputeWindowClosed,
+    
+    #[msg("Only the arbiter can rule on a dispute")]
+    UnauthorizedArbiter,
+    
+    #[msg("This pool's result is decided by moderator votes")]
+    ModeratorVotesRequired,
+    
+    #[msg("Only a member of the pool's moderator set can vote")]
+    UnauthorizedModerator,
+    
+    #[msg("Invalid moderator set. Up to 5 distinct keys with a threshold between 1 and the set size")]
+    InvalidModeratorSet,
+    
+    #[msg("This moderator has already voted")]
+    AlreadyVoted,
+    
+    #[msg("The dispute account is required when ruling on a challenged result")]
+    MissingDisputeAccount,
+    
+    #[msg("This pool has no attestor")]
+    AttestorNotSet,
+    
+    #[msg("The preceding instruction must be an ed25519 verification of the attested result")]
+    InvalidAttestation,
+    
+    #[msg("The attestation must be signed after the pool locked and not in the future")]
+    InvalidAttestationTime,
+    
+    #[msg("This pool has no resolver")]
+    ResolverNotSet,
+    
+    #[msg("Invalid resolver. Value feeds need a two-outcome pool and an external owner program")]
+    InvalidResolver,
+    
+    #[msg("Resolver account does not match the pool's resolver")]
+    InvalidResolverAccount,
+    
+    #[msg("The resolver has not published a result yet")]
+    ResultNotAvailable,
+    
+    #[msg("Only the proposed admin can accept the admin role")]
+    NotPendingAdmin,
+    
+    #[msg("Invalid maximum deadline extension")]
+    InvalidDeadlineExtension,
+    
+    #[msg("The new deadline is further out than the config allows")]
+    DeadlineTooFar,
+    
+    #[msg("This betting pool is paused")]
+    PoolPaused,
+    
+    #[msg("Only the guardian can pause or unpause")]
+    UnauthorizedGuardian,
+    
+    #[msg("Invalid cancellation cutoff")]
+    InvalidCancellationCutoff,
+    
+    #[msg("Bets can no longer be cancelled this close to the deadline")]
+    CancellationClosed,
+    
+    #[msg("The pool already has bets")]
+    BetsAlreadyPlaced,
+    
+    #[msg("The amount received is below the requested minimum")]
+    SlippageExceeded,
+    
+    #[msg("This instruction does not apply to the pool's market type")]
+    InvalidMarketType,
+    
+    #[msg("Odds must be set for every outcome and pay more than the stake")]
+    InvalidOdds,
+    
+    #[msg("The pool's liquidity cannot cover this bet at the current odds")]
+    LiquidityExceeded,
+    
+    #[msg("The pool's liquidity has already been returned")]
+    LiquidityAlreadyReturned,
+    
+    #[msg("Invalid liquidity parameter")]
+    InvalidLiquidityParameter,
+    
+    #[msg("The market has not been seeded yet")]
+    MarketNotSeeded,
+    
+    #[msg("The market has already been seeded")]
+    MarketAlreadySeeded,
+    
+    #[msg("The position does not hold that many shares")]
+    InsufficientShares,
+    
+    #[msg("LMSR markets cannot use a mint that charges a transfer fee")]
+    TransferFeeNotSupported,
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        constraint = betting_pool.pending_admin == Some(new_admin.key()) @ BettingError::NotPendingAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// The proposed admin, signing to prove it controls the key
+    pub new_admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // The pool address is seeded by its creator, so it does not move with the admin
+    let previous_admin = betting_pool.admin;
+    betting_pool.admin = ctx.accounts.new_admin.key();
+    betting_pool.pending_admin = None;
+
+    let clock = Clock::get()?;
+
+    emit!(AdminChanged {
+        betting_pool: betting_pool.key(),
+        previous_admin,
+        admin: betting_pool.admin,
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Admin changed for stream: {}", betting_pool.stream_id);
+    msg!("New admin: {}", betting_pool.admin);
+
+    Ok(())
+}
+
+// Event emitted when a proposed admin accepts the role
+#[event]
+pub struct AdminChanged {
+    pub betting_pool: Pubkey,
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+    pub changed_at: i64,
+}
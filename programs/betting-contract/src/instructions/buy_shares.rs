@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::pricing::lmsr;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct BuyShares<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// The user's position on the outcome, created by their first buy
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::LEN,
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            user.key().as_ref(),
+            &[outcome]
+        ],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Program config supplying bet limits and the pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user's token account the shares are paid from
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+pub fn handler(ctx: Context<BuyShares>, outcome: u8, shares: u64, max_cost: u64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let betting_pool = &ctx.accounts.betting_pool;
+    betting_pool.require_not_paused(config)?;
+    require!(betting_pool.is_lmsr(), BettingError::InvalidMarketType);
+    require!(betting_pool.liquidity_parameter > 0, BettingError::MarketNotSeeded);
+    require!(betting_pool.is_valid_outcome(outcome), BettingError::InvalidPrediction);
+    require!(betting_pool.is_betting_open(), BettingError::BettingClosed);
+    require!(shares > 0, BettingError::InsufficientFunds);
+
+    let cost = lmsr::buy_cost(
+        &betting_pool.outcome_shares(),
+        outcome as usize - 1,
+        shares,
+        betting_pool.liquidity_parameter,
+    )
+    .ok_or(BettingError::ArithmeticOverflow)?;
+    require!(cost <= max_cost, BettingError::SlippageExceeded);
+
+    // Bet limits are in lamports, so they only apply to SOL pools
+    if !betting_pool.is_token_pool() {
+        require!(cost >= config.min_bet, BettingError::BetTooSmall);
+        require!(cost <= config.max_bet, BettingError::BetTooLarge);
+    }
+
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    // LMSR pools never use a transfer-fee mint, so the market receives the full cost
+    let received = transfers::deposit_to_pool(
+        &ctx.accounts.vault,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        token,
+        cost,
+    )?;
+    require!(received >= cost, BettingError::InsufficientFunds);
+
+    let is_new_position = ctx.accounts.position.is_new();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    let bet_index = betting_pool.total_bet_count();
+    betting_pool.total_pool = betting_pool.total_pool
+        .checked_add(cost)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    let pool_outcome = betting_pool
+        .outcome_mut(outcome)
+        .ok_or(BettingError::InvalidPrediction)?;
+    pool_outcome.total_bets = pool_outcome.total_bets
+        .checked_add(cost)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    pool_outcome.bet_count = pool_outcome.bet_count
+        .checked_add(1)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    if is_new_position {
+        pool_outcome.position_count = pool_outcome.position_count
+            .checked_add(1)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+    }
+    pool_outcome.liability = pool_outcome.liability
+        .checked_add(shares)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+
+    let clock = Clock::get()?;
+    let betting_pool_key = betting_pool.key();
+    let position = &mut ctx.accounts.position;
+    if is_new_position {
+        position.user = ctx.accounts.user.key();
+        position.betting_pool = betting_pool_key;
+        position.prediction = outcome;
+        position.first_bet_at = clock.unix_timestamp;
+        position.is_paid_out = false;
+        position.bet_index = bet_index;
+        position.bump = ctx.bumps.position;
+    }
+    // The position's amount is its cost basis, its potential payout the shares it holds
+    position.amount = position.amount
+        .checked_add(cost)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.potential_payout = position.potential_payout
+        .checked_add(shares)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.bet_count = position.bet_count
+        .checked_add(1)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.last_bet_at = clock.unix_timestamp;
+
+    let prices = lmsr::prices(&betting_pool.outcome_shares(), betting_pool.liquidity_parameter)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+
+    emit!(SharesBought {
+        betting_pool: betting_pool_key,
+        position: position.key(),
+        user: position.user,
+        outcome,
+        shares,
+        cost,
+        position_shares: position.potential_payout,
+        prices,
+        bought_at: clock.unix_timestamp,
+    });
+
+    msg!("Shares bought on stream: {}", betting_pool.stream_id);
+    msg!("User: {}", position.user);
+    msg!("Bought {} shares of outcome {} for {} lamports", shares, outcome, cost);
+
+    Ok(())
+}
+
+// Event emitted when a user buys LMSR shares, with every outcome's price after the trade.
+// Prices are scaled by `lmsr::PRICE_SCALE`
+#[event]
+pub struct SharesBought {
+    pub betting_pool: Pubkey,
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub shares: u64,
+    pub cost: u64,
+    pub position_shares: u64,
+    pub prices: Vec<u64>,
+    pub bought_at: i64,
+}
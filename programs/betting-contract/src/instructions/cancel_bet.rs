@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+pub struct CancelBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The position being cancelled, closed with its rent returned to the user
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            user.key().as_ref(),
+            &[position.prediction]
+        ],
+        bump = position.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = user,
+        close = user,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user's token account receiving the refund
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CancelBet>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    // LMSR shares are sold back at the market price with sell_shares instead
+    require!(!ctx.accounts.betting_pool.is_lmsr(), BettingError::InvalidMarketType);
+
+    // Cancelling stops a while before the deadline so late odds can't be gamed
+    if !ctx.accounts.betting_pool.is_betting_open() {
+        return Err(BettingError::BettingClosed.into());
+    }
+    require!(ctx.accounts.betting_pool.is_cancellation_open()?, BettingError::CancellationClosed);
+
+    let amount = ctx.accounts.position.amount;
+    let prediction = ctx.accounts.position.prediction;
+    let cancellation_fee = ctx.accounts.betting_pool.calculate_cancellation_fee(amount);
+    let refund_amount = amount - cancellation_fee;
+
+    // The fee stays in the vault and is paid to the platform treasury with the platform fee
+    let token = TokenTransferAccounts::for_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if refund_amount > 0 {
+        transfers::pay_from_pool(
+            &ctx.accounts.betting_pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.system_program,
+            token,
+            refund_amount,
+        )?;
+    }
+
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    betting_pool.remove_position(&ctx.accounts.position)?;
+    betting_pool.record_fee_retained(cancellation_fee)?;
+    // The stake taken out may have been backing payouts on other outcomes
+    if betting_pool.is_fixed_odds() {
+        betting_pool.require_liability_covered()?;
+    }
+
+    let clock = Clock::get()?;
+
+    emit!(BetCancelled {
+        betting_pool: betting_pool.key(),
+        position: ctx.accounts.position.key(),
+        user: ctx.accounts.user.key(),
+        stream_id: betting_pool.stream_id.clone(),
+        prediction,
+        amount,
+        cancellation_fee,
+        refund_amount,
+        total_pool: betting_pool.total_pool,
+        outcome_bets: betting_pool.outcomes.iter().map(|outcome| outcome.total_bets).collect(),
+        cancelled_at: clock.unix_timestamp,
+    });
+
+    msg!("Bet cancelled for stream: {}", betting_pool.stream_id);
+    msg!("User: {}", ctx.accounts.user.key());
+    msg!("Refund: {} lamports, fee: {} lamports", refund_amount, cancellation_fee);
+
+    Ok(())
+}
+
+// Event emitted when a user cancels their position before betting closes
+#[event]
+pub struct BetCancelled {
+    pub betting_pool: Pubkey,
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub stream_id: String,
+    pub prediction: u8,
+    pub amount: u64,
+    pub cancellation_fee: u64,
+    pub refund_amount: u64,
+    pub total_pool: u64,
+    pub outcome_bets: Vec<u64>,
+    pub cancelled_at: i64,
+}
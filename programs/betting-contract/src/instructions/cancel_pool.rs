@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct CancelPool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+    #[account(mut)]
+    pub signer: Signer<'info>, // Can be admin or moderator
+}
+
+pub fn handler(ctx: Context<CancelPool>) -> Result<()> {
+    let signer_key = ctx.accounts.signer.key();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    // Check if signer is admin or moderator
+    if signer_key != betting_pool.admin && signer_key != betting_pool.moderator {
+        return Err(BettingError::UnauthorizedAdmin.into());
+    }
+    // A declared result can no longer be called off
+    if betting_pool.is_result_declared() {
+        return Err(BettingError::WinnerAlreadyDeclared.into());
+    }
+    if betting_pool.is_cancelled() {
+        return Err(BettingError::PoolCancelled.into());
+    }
+    let clock = Clock::get()?;
+    let betting_pool_key = betting_pool.key();
+    betting_pool.set_status(betting_pool_key, PoolStatus::Cancelled)?;
+    // Every position is owed back in full, or LMSR shares their value at the final prices
+    let total_pool = betting_pool.total_pool;
+    let total_bet_count = betting_pool.total_bet_count();
+    let total_position_count = betting_pool.total_position_count();
+    let refund_total = betting_pool.refund_total();
+    betting_pool.open_settlement(total_position_count, refund_total);
+    let stream_id = betting_pool.stream_id.clone();
+    emit!(BettingPoolCancelled {
+        betting_pool: betting_pool_key,
+        cancelled_by: signer_key,
+        stream_id: stream_id.clone(),
+        total_pool,
+        total_bet_count,
+        cancelled_at: clock.unix_timestamp,
+    });
+    msg!("Betting pool cancelled for stream: {}", stream_id);
+    msg!("Cancelled by: {}", signer_key);
+    msg!("Refundable total: {} lamports ({} bets)", total_pool, total_bet_count);
+    Ok(())
+}
+
+// Event emitted when a betting pool is cancelled
+#[event]
+pub struct BettingPoolCancelled {
+    pub betting_pool: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub stream_id: String,
+    pub total_pool: u64,
+    pub total_bet_count: u32,
+    pub cancelled_at: i64,
+}
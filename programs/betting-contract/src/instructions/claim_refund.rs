@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    
+    /// The position being refunded
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            position.user.as_ref(),
+            &[position.prediction]
+        ],
+        bump = position.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = user,
+        close = user,
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: Validated against `position.user` by the `has_one` constraint above
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+    
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The user's token account receiving the refund
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
+    
+    let betting_pool_key = ctx.accounts.betting_pool.key();
+    let position_key = ctx.accounts.position.key();
+    let user_key = ctx.accounts.user.key();
+    
+    
+    if !ctx.accounts.betting_pool.is_cancelled() && !ctx.accounts.betting_pool.is_refund_outcome() {
+        return Err(BettingError::RefundsNotAvailable.into());
+    }
+    // A void or draw result can still be disputed and overturned until its window closes.
+    // Pausing blocks them too, only cancelled pools keep refunding while paused
+    if !ctx.accounts.betting_pool.is_cancelled() {
+        ctx.accounts.betting_pool.require_settleable()?;
+        ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    }
+    
+    
+    if ctx.accounts.position.is_paid_out {
+        return Err(BettingError::BetAlreadyPaidOut.into());
+    }
+    
+    // Cancelled pools refund every bet in full; void and drawn pools refund
+    // proportionally after any fees the pool's refund fee policy charges.
+    // LMSR shares are bought back at the final prices either way
+    let refund_share = ctx.accounts.betting_pool.position_refund(&ctx.accounts.position);
+    let refund_amount = ctx.accounts.betting_pool.next_claim_amount(refund_share);
+    
+    
+    
+    let stream_id = ctx.accounts.betting_pool.stream_id.clone();
+    let prediction = ctx.accounts.position.prediction;
+    
+    // Return the stake to the bettor
+    let token = TokenTransferAccounts::for_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::pay_from_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program,
+        token,
+        refund_amount,
+    )?;
+    
+    ctx.accounts.betting_pool.record_claim(refund_amount, true)?;
+    
+    // Mark this position as settled so it cannot be refunded twice; it is closed and its rent returned
+    ctx.accounts.position.is_paid_out = true;
+    ctx.accounts.betting_pool.record_position_closed()?;
+    ctx.accounts.betting_pool.advance_settlement(betting_pool_key)?;
+    
+    let clock = Clock::get()?;
+    
+    
+    emit!(BetRefunded {
+        betting_pool: betting_pool_key,
+        position: position_key,
+        user: user_key,
+        stream_id: stream_id.clone(),
+        prediction,
+        refund_amount,
+        refunded_at: clock.unix_timestamp,
+    });
+    
+    
+    msg!("Refund completed for stream: {}", stream_id);
+    msg!("User: {}", user_key);
+    msg!("Refund amount: {} lamports", refund_amount);
+    
+    Ok(())
+}
+
+// Event emitted when a position is refunded
+#[event]
+pub struct BetRefunded {
+    pub betting_pool: Pubkey,
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub stream_id: String,
+    pub prediction: u8,
+    pub refund_amount: u64,
+    pub refunded_at: i64,
+}
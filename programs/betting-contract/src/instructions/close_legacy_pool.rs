@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers;
+
+#[derive(Accounts)]
+pub struct CloseLegacyPool<'info> {
+    /// A pool created before pools were namespaced by creator
+    #[account(
+        mut,
+        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+        close = admin,
+    )]
+    pub betting_pool: Account<'info, LegacyBettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Pool admin, receives the creator fee and the pool's rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Validated against the pool's platform treasury, receives the platform fee and rounding dust
+    #[account(
+        mut,
+        constraint = platform_treasury.key() == betting_pool.platform_treasury @ BettingError::InvalidPlatformWallet
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<CloseLegacyPool>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, BettingError::ProgramPaused);
+    let betting_pool = &ctx.accounts.betting_pool;
+
+    // Every bet must be settled, so what is left are the fees and rounding dust
+    require!(
+        betting_pool.winner_declared && betting_pool.is_payout_complete,
+        BettingError::SettlementIncomplete
+    );
+
+    // Fees the previous program version already paid out are no longer in the pool,
+    // so the creator fee is capped at what is left and the platform gets the rest
+    let surplus = transfers::legacy_pool_surplus(betting_pool)?;
+    let creator_fee = betting_pool.creator_fee()?.min(surplus);
+    let platform_amount = surplus - creator_fee;
+    if platform_amount > 0 {
+        transfers::pay_from_legacy_pool(betting_pool, &ctx.accounts.platform_treasury, platform_amount)?;
+    }
+    // The creator fee and the rent go to the admin as `close = admin` empties the pool
+
+    let clock = Clock::get()?;
+
+    emit!(LegacyPoolClosed {
+        betting_pool: betting_pool.key(),
+        stream_id: betting_pool.stream_id.clone(),
+        creator_fee,
+        platform_amount,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!("Legacy pool closed for stream: {}", betting_pool.stream_id);
+    msg!("Creator fee: {} lamports", creator_fee);
+    msg!("Platform fee and dust: {} lamports", platform_amount);
+
+    Ok(())
+}
+
+// Event emitted when a wound-down legacy pool is closed
+#[event]
+pub struct LegacyPoolClosed {
+    pub betting_pool: Pubkey,
+    pub stream_id: String,
+    pub creator_fee: u64,
+    pub platform_amount: u64,
+    pub closed_at: i64,
+}
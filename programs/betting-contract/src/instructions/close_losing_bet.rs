@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct CloseLosingBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// The losing position being closed
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            position.user.as_ref(),
+            &[position.prediction]
+        ],
+        bump = position.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = user,
+        close = user,
+    )]
+    pub position: Account<'info, Position>,
+
+    /// CHECK: Validated against `position.user` by the `has_one` constraint above, receives the rent
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// Anyone can close a losing position, the rent always goes back to its owner
+    pub payer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CloseLosingBet>) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    betting_pool.require_settleable()?;
+
+    // Void and draw refund every position through claim_refund
+    require!(!betting_pool.is_refund_outcome(), BettingError::BetIsWinner);
+    require!(
+        !ctx.accounts.position.is_winner(betting_pool.winning_outcome),
+        BettingError::BetIsWinner
+    );
+
+    betting_pool.record_position_closed()?;
+
+    let clock = Clock::get()?;
+
+    emit!(LosingBetClosed {
+        betting_pool: betting_pool.key(),
+        position: ctx.accounts.position.key(),
+        user: ctx.accounts.user.key(),
+        prediction: ctx.accounts.position.prediction,
+        bet_amount: ctx.accounts.position.amount,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!("Losing position closed for stream: {}", betting_pool.stream_id);
+    msg!("Rent returned to: {}", ctx.accounts.user.key());
+
+    Ok(())
+}
+
+// Event emitted when a losing position is closed
+#[event]
+pub struct LosingBetClosed {
+    pub betting_pool: Pubkey,
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub prediction: u8,
+    pub bet_amount: u64,
+    pub closed_at: i64,
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+        close = admin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Pool admin, receives the pool's and the vaults' rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Validated against the pool's platform treasury, receives rounding dust
+    #[account(
+        mut,
+        constraint = platform_treasury.key() == betting_pool.platform_treasury @ BettingError::InvalidPlatformWallet
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool, receives fees withheld in a Token-2022 vault. Leave out for native SOL pools
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The platform treasury's token account receiving rounding dust
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = platform_treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClosePool>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &ctx.accounts.betting_pool;
+
+    // The pool must be fully settled (or cancelled and fully refunded) with every position closed
+    require!(
+        betting_pool.status == PoolStatus::Settled || betting_pool.is_cancelled(),
+        BettingError::SettlementIncomplete
+    );
+    require!(betting_pool.is_payout_complete(), BettingError::SettlementIncomplete);
+    require!(betting_pool.all_positions_closed(), BettingError::PositionsStillOpen);
+
+    // Sweep rounding dust left in the vault to the treasury
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    let dust = transfers::vault_surplus(&ctx.accounts.vault, token.as_ref())?;
+    if dust > 0 {
+        transfers::pay_from_pool(
+            betting_pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.platform_treasury,
+            &ctx.accounts.system_program,
+            token,
+            dust,
+        )?;
+    }
+
+    // Return the vaults' rent to the admin; the pool account itself is closed by `close = admin`
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::close_vaults(
+        betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        token,
+    )?;
+
+    let clock = Clock::get()?;
+
+    emit!(BettingPoolClosed {
+        betting_pool: betting_pool.key(),
+        admin: betting_pool.admin,
+        stream_id: betting_pool.stream_id.clone(),
+        dust_swept: dust,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!("Betting pool closed for stream: {}", betting_pool.stream_id);
+    msg!("Dust swept to treasury: {}", dust);
+
+    Ok(())
+}
+
+// Event emitted when a settled betting pool is closed
+#[event]
+pub struct BettingPoolClosed {
+    pub betting_pool: Pubkey,
+    pub admin: Pubkey,
+    pub stream_id: String,
+    pub dust_swept: u64,
+    pub closed_at: i64,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct DeclareLegacyResult<'info> {
+    /// A pool created before pools were namespaced by creator
+    #[account(
+        mut,
+        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, LegacyBettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub signer: Signer<'info>, // Admin or moderator
+}
+
+pub fn handler(ctx: Context<DeclareLegacyResult>, winning_outcome: u8) -> Result<()> {
+    require!(!ctx.accounts.config.paused, BettingError::ProgramPaused);
+    let signer_key = ctx.accounts.signer.key();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    if signer_key != betting_pool.admin && signer_key != betting_pool.moderator {
+        return Err(BettingError::UnauthorizedAdmin.into());
+    }
+    if betting_pool.winner_declared {
+        return Err(BettingError::WinnerAlreadyDeclared.into());
+    }
+
+    // Player 1 or 2 wins, or the pool is void or drawn and every bet is refunded
+    betting_pool.winning_outcome = winning_outcome;
+    if !betting_pool.is_refund_outcome() {
+        require!(winning_outcome == 1 || winning_outcome == 2, BettingError::InvalidWinningOutcome);
+        require!(betting_pool.outcome_bets(winning_outcome) > 0, BettingError::NoBetsOnWinningOutcome);
+    }
+    betting_pool.winner_declared = true;
+    betting_pool.is_payout_complete = betting_pool.player1_bet_count == 0 && betting_pool.player2_bet_count == 0;
+
+    let clock = Clock::get()?;
+
+    emit!(LegacyResultDeclared {
+        betting_pool: betting_pool.key(),
+        stream_id: betting_pool.stream_id.clone(),
+        winning_outcome,
+        declared_by: signer_key,
+        declared_at: clock.unix_timestamp,
+    });
+
+    msg!("Legacy pool result declared for stream: {}", betting_pool.stream_id);
+    msg!("Winning outcome: {}", winning_outcome);
+
+    Ok(())
+}
+
+// Event emitted when a legacy pool's result is declared
+#[event]
+pub struct LegacyResultDeclared {
+    pub betting_pool: Pubkey,
+    pub stream_id: String,
+    pub winning_outcome: u8,
+    pub declared_by: Pubkey,
+    pub declared_at: i64,
+}
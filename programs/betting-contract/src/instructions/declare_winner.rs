@@ -2,53 +2,99 @@
 
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::constants::{OUTCOME_DRAW, OUTCOME_VOID};
 use crate::error::BettingError;
 
 #[derive(Accounts)]
 pub struct DeclareWinner<'info> {
     #[account(
         mut,
-        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
         bump = betting_pool.bump,
     )]
     pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     #[account(mut)]
     pub signer: Signer<'info>, // Can be admin or moderator
 }
 
 pub fn handler(ctx: Context<DeclareWinner>, winning_outcome: u8) -> Result<()> {
-    // Validate winning outcome (1 = Player 1 wins, 2 = Player 2 wins)
-    if winning_outcome != 1 && winning_outcome != 2 {
-        return Err(BettingError::InvalidWinningOutcome.into());
-    }
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
     let signer_key = ctx.accounts.signer.key();
     let betting_pool = &mut ctx.accounts.betting_pool;
     // Check if signer is admin or moderator
     if signer_key != betting_pool.admin && signer_key != betting_pool.moderator {
         return Err(BettingError::UnauthorizedAdmin.into());
     }
-    if betting_pool.winner_declared {
+    // With a moderator set no single key decides the result, it goes through vote_result
+    if betting_pool.uses_moderator_votes() {
+        return Err(BettingError::ModeratorVotesRequired.into());
+    }
+    require_resolvable(betting_pool, winning_outcome)?;
+    declare_result(betting_pool, winning_outcome)
+}
+
+/// Check `winning_outcome` can be declared on the pool now, locking it if its deadline has passed
+pub(crate) fn require_resolvable(betting_pool: &mut Account<BettingPool>, winning_outcome: u8) -> Result<()> {
+    // Validate winning outcome (0 = Void, 1..=N = that outcome wins, 255 = Draw)
+    let is_refund_outcome = winning_outcome == OUTCOME_VOID || winning_outcome == OUTCOME_DRAW;
+    if !betting_pool.is_valid_outcome(winning_outcome) && !is_refund_outcome {
+        return Err(BettingError::InvalidWinningOutcome.into());
+    }
+    if betting_pool.is_result_declared() {
         return Err(BettingError::WinnerAlreadyDeclared.into());
     }
+    if betting_pool.is_cancelled() {
+        return Err(BettingError::PoolCancelled.into());
+    }
     let clock = Clock::get()?;
-    betting_pool.winner_declared = true;
-    betting_pool.winning_outcome = winning_outcome;
     let betting_pool_key = betting_pool.key();
+    // Pools lock automatically at the deadline; before it they must be locked with lock_pool
+    if betting_pool.status == PoolStatus::Open {
+        require!(
+            clock.unix_timestamp >= betting_pool.betting_deadline,
+            BettingError::PoolNotLocked
+        );
+        let betting_deadline = betting_pool.betting_deadline;
+        betting_pool.lock(betting_pool_key, betting_deadline)?;
+    }
+    require!(
+        clock.unix_timestamp >= betting_pool.resolution_opens_at()?,
+        BettingError::ResolutionTooEarly
+    );
+    Ok(())
+}
+
+/// Declare `winning_outcome` on a locked pool and open its dispute window.
+/// Callers check authority and `require_resolvable` first.
+pub(crate) fn declare_result(betting_pool: &mut Account<BettingPool>, winning_outcome: u8) -> Result<()> {
+    let clock = Clock::get()?;
+    let betting_pool_key = betting_pool.key();
+    betting_pool.set_status(betting_pool_key, PoolStatus::Resolved)?;
+    let (winning_bets, winning_bet_count, winning_position_count) = betting_pool.apply_result(winning_outcome)?;
+    // The result is proposed: bettors can dispute it until the window closes
+    betting_pool.dispute_deadline = clock.unix_timestamp
+        .checked_add(betting_pool.dispute_window)
+        .ok_or(BettingError::ArithmeticOverflow)?;
     let stream_id = betting_pool.stream_id.clone();
     let total_pool = betting_pool.total_pool;
-    let player1_bets = betting_pool.player1_bets;
-    let player2_bets = betting_pool.player2_bets;
-    let player1_bet_count = betting_pool.player1_bet_count;
-    let player2_bet_count = betting_pool.player2_bet_count;
+    let losing_bets = total_pool - winning_bets;
     let platform_fee = betting_pool.calculate_platform_fee();
     let prize_pool = betting_pool.prize_pool();
-    let (winning_bets, winning_bet_count, losing_bets) = if winning_outcome == 1 {
-        (player1_bets, player1_bet_count, player2_bets)
-    } else {
-        (player2_bets, player2_bet_count, player1_bets)
-    };
-    if winning_bets == 0 {
-        return Err(BettingError::NoBetsOnWinningOutcome.into());
+    // A void or draw with no bets and no fees has nothing left to pay
+    if betting_pool.is_payout_complete() {
+        betting_pool.set_status(betting_pool_key, PoolStatus::Settled)?;
     }
     emit!(WinnerDeclared {
         betting_pool: betting_pool_key,
@@ -58,13 +104,23 @@ pub fn handler(ctx: Context<DeclareWinner>, winning_outcome: u8) -> Result<()> {
         total_pool,
         winning_bets,
         winning_bet_count,
+        winning_position_count,
         losing_bets,
         platform_fee,
         prize_pool,
         declared_at: clock.unix_timestamp,
+        dispute_deadline: betting_pool.dispute_deadline,
     });
     msg!("Winner declared for stream: {}", stream_id);
-    msg!("Winning outcome: Player {}", winning_outcome);
+    match winning_outcome {
+        OUTCOME_VOID => msg!("Winning outcome: Void (all bets refunded)"),
+        OUTCOME_DRAW => msg!("Winning outcome: Draw (all bets refunded)"),
+        _ => msg!(
+            "Winning outcome: {} ({})",
+            winning_outcome,
+            betting_pool.outcome(winning_outcome).map_or("", |outcome| outcome.label.as_str())
+        ),
+    }
     msg!("Total pool: {} lamports", total_pool);
     msg!("Winning bets total: {} lamports ({} bets)", winning_bets, winning_bet_count);
     msg!("Losing bets total: {} lamports", losing_bets);
@@ -83,8 +139,10 @@ pub struct WinnerDeclared {
     pub total_pool: u64,
     pub winning_bets: u64,
     pub winning_bet_count: u32,
+    pub winning_position_count: u32,
     pub losing_bets: u64,
     pub platform_fee: u64,
     pub prize_pool: u64,
     pub declared_at: i64,
+    pub dispute_deadline: i64,
 }
\ No newline at end of file
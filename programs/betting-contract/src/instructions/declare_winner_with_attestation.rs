@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::*;
+use crate::attestation;
+use crate::error::BettingError;
+use super::declare_winner::{declare_result, require_resolvable};
+
+/// Declares a result signed by the pool's attestor.
+///
+/// The transaction must verify the attestor's signature over
+/// `attestation::attestation_message(pool, winning_outcome, attested_at)` with an ed25519
+/// program instruction placed right before this one. Anyone can submit it.
+#[derive(Accounts)]
+pub struct DeclareWinnerWithAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// CHECK: The instructions sysvar, checked by address
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<DeclareWinnerWithAttestation>, winning_outcome: u8, attested_at: i64) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    let attestor = betting_pool.attestor.ok_or(BettingError::AttestorNotSet)?;
+    // A single attestor key must not outvote a moderator set
+    require!(!betting_pool.uses_moderator_votes(), BettingError::ModeratorVotesRequired);
+
+    let message = attestation::attestation_message(&betting_pool.key(), winning_outcome, attested_at);
+    attestation::verify_ed25519_signature(&ctx.accounts.instructions_sysvar, &attestor, &message)?;
+
+    require_resolvable(betting_pool, winning_outcome)?;
+
+    // A result signed before betting closed, or dated in the future, cannot be trusted
+    let clock = Clock::get()?;
+    require!(
+        attested_at >= betting_pool.locked_at && attested_at <= clock.unix_timestamp,
+        BettingError::InvalidAttestationTime
+    );
+
+    msg!("Result attested by: {}", attestor);
+    declare_result(betting_pool, winning_outcome)
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+/// Adds admin funds backing a fixed-odds pool's payouts.
+/// They come back with `withdraw_liquidity` once the pool settles.
+#[derive(Accounts)]
+pub struct DepositLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin's token account the liquidity is paid from
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program,
+    )]
+    pub admin_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    require!(ctx.accounts.betting_pool.is_fixed_odds(), BettingError::InvalidMarketType);
+    require!(ctx.accounts.betting_pool.is_betting_open(), BettingError::BettingClosed);
+    require!(amount > 0, BettingError::InsufficientFunds);
+
+    let token = TokenTransferAccounts::for_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    // Count what the vault actually received (Token-2022 transfer fees are withheld)
+    let amount = transfers::deposit_to_pool(
+        &ctx.accounts.vault,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        token,
+        amount,
+    )?;
+
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    betting_pool.liquidity = betting_pool.liquidity
+        .checked_add(amount)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+
+    let clock = Clock::get()?;
+
+    emit!(LiquidityDeposited {
+        betting_pool: betting_pool.key(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        liquidity: betting_pool.liquidity,
+        deposited_at: clock.unix_timestamp,
+    });
+
+    msg!("Liquidity deposited for stream: {}", betting_pool.stream_id);
+    msg!("Amount: {} lamports, total liquidity: {} lamports", amount, betting_pool.liquidity);
+
+    Ok(())
+}
+
+// Event emitted when the admin adds liquidity to a fixed-odds pool
+#[event]
+pub struct LiquidityDeposited {
+    pub betting_pool: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub liquidity: u64,
+    pub deposited_at: i64,
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::constants::{OUTCOME_DRAW, OUTCOME_VOID};
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct DisputeResult<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Holds the challenger's bond until the arbiter rules
+    #[account(
+        init,
+        payer = challenger,
+        space = Dispute::LEN,
+        seeds = [b"dispute", betting_pool.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    /// Any of the challenger's positions on the pool, proving they are a bettor
+    #[account(
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            challenger.key().as_ref(),
+            &[position.prediction]
+        ],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DisputeResult>, proposed_outcome: u8) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // Only a freshly declared result can be challenged, and only inside its window
+    if betting_pool.is_cancelled() {
+        return Err(BettingError::PoolCancelled.into());
+    }
+    require!(betting_pool.status == PoolStatus::Resolved, BettingError::InvalidPoolStatus);
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp < betting_pool.dispute_deadline,
+        BettingError::DisputeWindowClosed
+    );
+
+    let is_refund_outcome = proposed_outcome == OUTCOME_VOID || proposed_outcome == OUTCOME_DRAW;
+    if !betting_pool.is_valid_outcome(proposed_outcome) && !is_refund_outcome {
+        return Err(BettingError::InvalidWinningOutcome.into());
+    }
+    require!(
+        proposed_outcome != betting_pool.winning_outcome,
+        BettingError::InvalidWinningOutcome
+    );
+
+    // Post the bond into the dispute account
+    let bond = betting_pool.dispute_bond;
+    if bond > 0 {
+        let transfer_instruction = system_program::Transfer {
+            from: ctx.accounts.challenger.to_account_info(),
+            to: ctx.accounts.dispute.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_instruction);
+        system_program::transfer(cpi_context, bond)?;
+    }
+
+    let betting_pool_key = betting_pool.key();
+    betting_pool.set_status(betting_pool_key, PoolStatus::Disputed)?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.betting_pool = betting_pool_key;
+    dispute.challenger = ctx.accounts.challenger.key();
+    dispute.disputed_outcome = betting_pool.winning_outcome;
+    dispute.proposed_outcome = proposed_outcome;
+    dispute.bond = bond;
+    dispute.created_at = clock.unix_timestamp;
+    dispute.bump = ctx.bumps.dispute;
+
+    emit!(ResultDisputed {
+        betting_pool: betting_pool_key,
+        dispute: dispute.key(),
+        challenger: dispute.challenger,
+        disputed_outcome: dispute.disputed_outcome,
+        proposed_outcome,
+        bond,
+        disputed_at: clock.unix_timestamp,
+    });
+
+    msg!("Result disputed for stream: {}", betting_pool.stream_id);
+    msg!("Challenger: {}", dispute.challenger);
+    msg!("Bond: {} lamports", bond);
+
+    Ok(())
+}
+
+// Event emitted when a declared result is disputed
+#[event]
+pub struct ResultDisputed {
+    pub betting_pool: Pubkey,
+    pub dispute: Pubkey,
+    pub challenger: Pubkey,
+    pub disputed_outcome: u8,
+    pub proposed_outcome: u8,
+    pub bond: u64,
+    pub disputed_at: i64,
+}
@@ -1,42 +1,150 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::constants::{MAX_OUTCOMES, MAX_OUTCOME_LABEL_LEN, MIN_OUTCOMES};
 use crate::error::BettingError;
+use crate::transfers;
 
 #[derive(Accounts)]
-#[instruction(stream_id: String, betting_deadline: i64, moderator_pubkey: Pubkey,  platform_treasury: Pubkey)]
+#[instruction(
+    stream_id: String,
+    match_number: u64,
+    betting_deadline: i64,
+    moderator_pubkey: Pubkey,
+    creator_fee_rate: Option<u16>,
+    refund_fee_policy: RefundFeePolicy,
+    outcome_labels: Vec<String>,
+    resolver: Option<ResolverConfig>,
+    market_type: MarketType
+)]
 pub struct Initialize<'info> {
     #[account(
         init,
         payer = admin,
-        space = BettingPool::LEN,
-        seeds = [b"betting_pool", stream_id.as_bytes()],
+        space = BettingPool::space(outcome_labels.len()),
+        seeds = [
+            b"betting_pool",
+            admin.key().as_ref(),
+            stream_id.as_bytes(),
+            &match_number.to_le_bytes(),
+        ],
         bump
     )]
     pub betting_pool: Account<'info, BettingPool>,
+    /// System-owned vault that will hold the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
     #[account(mut)]
     pub admin: Signer<'info>,
-    /// CHECK: Platform treasury can be any address
-    pub platform_treasury: UncheckedAccount<'info>,
+    /// Program config supplying the pool's fee terms and treasury
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    /// Mint bets are made in. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Token account holding a token pool's stakes, owned by the betting pool
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = betting_pool,
+        token::token_program = token_program,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token or Token-2022 program owning the mint
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<Initialize>,
     stream_id: String,
+    match_number: u64,
     betting_deadline: i64,
     moderator_pubkey: Pubkey,
-    platform_treasury: Pubkey,
+    creator_fee_rate: Option<u16>,
+    refund_fee_policy: RefundFeePolicy,
+    outcome_labels: Vec<String>,
+    resolver: Option<ResolverConfig>,
+    market_type: MarketType,
 ) -> Result<()> {
+    let config = &ctx.accounts.config;
+    require!(!config.paused, BettingError::ProgramPaused);
+    
+    // Fee terms and treasury come from the program config.
+    // A fixed-odds or LMSR creator earns through the odds or the market, so takes no fee on top
+    let creator_fee_rate = match market_type {
+        MarketType::Parimutuel => config.creator_fee_rate(creator_fee_rate)?,
+        MarketType::FixedOdds | MarketType::Lmsr => {
+            require!(creator_fee_rate.unwrap_or(0) == 0, BettingError::InvalidFeeRate);
+            0
+        }
+    };
+    let platform_fee_rate = config.platform_fee_rate;
+    let platform_treasury = config.platform_treasury;
+    let resolution_delay = config.min_resolution_delay;
+    let dispute_window = config.dispute_window;
+    let dispute_bond = config.dispute_bond;
+    let cancellation_cutoff = config.cancellation_cutoff;
+    
     // Validate stream ID length
     if stream_id.len() > 32 {
         return Err(BettingError::StreamIdTooLong.into());
     }
     
+    // Validate outcomes (2 for a 1v1, up to 16 for free-for-alls)
+    if outcome_labels.len() < MIN_OUTCOMES as usize || outcome_labels.len() > MAX_OUTCOMES as usize {
+        return Err(BettingError::InvalidOutcomeCount.into());
+    }
+    if outcome_labels.iter().any(|label| label.len() > MAX_OUTCOME_LABEL_LEN) {
+        return Err(BettingError::OutcomeLabelTooLong.into());
+    }
+    if let Some(resolver) = &resolver {
+        resolver.validate(outcome_labels.len())?;
+    }
+    
+    // Token pools need their vault created alongside the pool
+    let mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    if mint.is_some() != ctx.accounts.token_vault.is_some() {
+        return Err(BettingError::MissingTokenAccounts.into());
+    }
+    // Every LMSR share redeems for exactly one unit, which a transfer fee would eat into
+    if let Some(mint) = &ctx.accounts.mint {
+        if market_type == MarketType::Lmsr && transfers::has_transfer_fee(mint)? {
+            return Err(BettingError::TransferFeeNotSupported.into());
+        }
+    }
+    
     // Validate betting deadline is in the future
     let clock = Clock::get()?;
     if betting_deadline <= clock.unix_timestamp {
         return Err(BettingError::InvalidDeadline.into());
     }
+    // Fund the vault up to its rent-exempt minimum so stakes can come and go freely
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let vault_top_up = rent_exempt_minimum.saturating_sub(ctx.accounts.vault.lamports());
+    if vault_top_up > 0 {
+        let transfer_instruction = system_program::Transfer {
+            from: ctx.accounts.admin.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            transfer_instruction,
+        );
+        system_program::transfer(cpi_context, vault_top_up)?;
+    }
+    
     let pool_key = ctx.accounts.betting_pool.key();
     let admin_key = ctx.accounts.admin.key();
     let stream_id_clone = stream_id.clone();
@@ -46,40 +154,91 @@ pub fn handler(
     
     // Initialize betting pool with your specifications
     betting_pool.admin = admin_key;
+    betting_pool.pending_admin = None;
 
     betting_pool.moderator = moderator_pubkey;
+    betting_pool.moderators = Vec::new();
+    betting_pool.moderator_threshold = 0;
+    betting_pool.attestor = None;
+    betting_pool.resolver = resolver;
+    betting_pool.creator = admin_key;
     betting_pool.stream_id = stream_id.clone();
+    betting_pool.match_number = match_number;
     betting_pool.total_pool = 0;
-    betting_pool.player1_bets = 0;
-    betting_pool.player2_bets = 0;
-    betting_pool.player1_bet_count = 0;
-    betting_pool.player2_bet_count = 0;
-    betting_pool.winner_declared = false;
+    betting_pool.market_type = market_type;
+    betting_pool.liquidity = 0;
+    betting_pool.liquidity_parameter = 0;
+    betting_pool.outcomes = outcome_labels
+        .into_iter()
+        .map(|label| Outcome {
+            label,
+            total_bets: 0,
+            bet_count: 0,
+            position_count: 0,
+            odds: 0,
+            liability: 0,
+        })
+        .collect();
+    betting_pool.status = PoolStatus::Open;
+    betting_pool.paused = false;
     betting_pool.winning_outcome = 0; // 0 means not set yet
     betting_pool.betting_deadline = betting_deadline;
-    betting_pool.creator_fee_rate = 500;
-    betting_pool.platform_fee_rate = 250;
+    betting_pool.original_deadline = betting_deadline;
+    betting_pool.creator_fee_rate = creator_fee_rate;
+    betting_pool.platform_fee_rate = platform_fee_rate;
     betting_pool.platform_treasury = platform_treasury;
-    betting_pool.is_payout_complete = false;
+    betting_pool.locked_at = 0;
+    betting_pool.resolution_delay = resolution_delay;
+    betting_pool.dispute_window = dispute_window;
+    betting_pool.dispute_bond = dispute_bond;
+    betting_pool.dispute_deadline = 0;
+    betting_pool.cancellation_cutoff = cancellation_cutoff;
+    betting_pool.cancellation_fee_rate = 0;
+    betting_pool.refund_fee_policy = refund_fee_policy;
+    betting_pool.mint = mint;
+    betting_pool.creator_fee_paid = 0;
+    betting_pool.platform_fee_paid = 0;
+    betting_pool.total_paid_to_winners = 0;
+    betting_pool.total_refunded = 0;
+    betting_pool.liquidity_returned = 0;
+    betting_pool.fees_retained = 0;
+    betting_pool.fees_retained_paid = 0;
+    betting_pool.remaining_claimable = 0;
+    betting_pool.remaining_claims = 0;
     betting_pool.created_at = clock.unix_timestamp;
     betting_pool.bump = ctx.bumps.betting_pool;
+    betting_pool.vault_bump = ctx.bumps.vault;
     
     // Store values for event and logging before dropping mutable reference
     let betting_deadline = betting_pool.betting_deadline;
     let created_at = betting_pool.created_at;
     let platform_fee_rate = betting_pool.platform_fee_rate;
+    let outcome_labels: Vec<String> = betting_pool.outcomes.iter().map(|outcome| outcome.label.clone()).collect();
     
     // Emit event for frontend to track
     emit!(BettingPoolCreated {
         pool: pool_key,
         admin: admin_key,
         stream_id: stream_id_clone.clone(),
+        match_number,
         betting_deadline,
+        outcome_labels: outcome_labels.clone(),
+        market_type,
+        mint,
         created_at,
     });
     
-    msg!("Betting pool created for stream: {}", stream_id_clone);
+    msg!("Betting pool created for stream: {} (match {})", stream_id_clone, match_number);
     msg!("Betting closes at: {}", betting_deadline);
+    msg!("Outcomes: {}", outcome_labels.join(", "));
+    if let Some(mint) = mint {
+        msg!("Bets are made in token: {}", mint);
+    }
+    match market_type {
+        MarketType::Parimutuel => {}
+        MarketType::FixedOdds => msg!("Bets are paid at fixed odds"),
+        MarketType::Lmsr => msg!("Outcome shares are priced by an LMSR market"),
+    }
     msg!("Platform fee: {}%", platform_fee_rate as f64 / 100.0);
     
     Ok(())
@@ -91,6 +250,10 @@ pub struct BettingPoolCreated {
     pub pool: Pubkey,
     pub admin: Pubkey,
     pub stream_id: String,
+    pub match_number: u64,
     pub betting_deadline: i64,
+    pub outcome_labels: Vec<String>,
+    pub market_type: MarketType,
+    pub mint: Option<Pubkey>,
     pub created_at: i64,
 }
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::program::BettingContract;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ProgramConfig::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    
+    /// Must be the program's upgrade authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, BettingContract>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ BettingError::UnauthorizedConfigAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(&params)?;
+    config.bump = ctx.bumps.config;
+    
+    let clock = Clock::get()?;
+    
+    emit!(ConfigUpdated {
+        config: config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        authority: config.authority,
+        platform_treasury: config.platform_treasury,
+        platform_fee_rate: config.platform_fee_rate,
+        default_creator_fee_rate: config.default_creator_fee_rate,
+        max_creator_fee_rate: config.max_creator_fee_rate,
+        min_bet: config.min_bet,
+        max_bet: config.max_bet,
+        paused: config.paused,
+        min_resolution_delay: config.min_resolution_delay,
+        dispute_window: config.dispute_window,
+        dispute_bond: config.dispute_bond,
+        arbiter: config.arbiter,
+        max_deadline_extension: config.max_deadline_extension,
+        guardian: config.guardian,
+        cancellation_cutoff: config.cancellation_cutoff,
+        updated_at: clock.unix_timestamp,
+    });
+    
+    msg!("Program config initialized");
+    msg!("Platform authority: {}", config.authority);
+    msg!("Platform treasury: {}", config.platform_treasury);
+    
+    Ok(())
+}
+
+// Event emitted whenever the program config is created or changed
+#[event]
+pub struct ConfigUpdated {
+    pub config: Pubkey,
+    pub updated_by: Pubkey,
+    pub authority: Pubkey,
+    pub platform_treasury: Pubkey,
+    pub platform_fee_rate: u16,
+    pub default_creator_fee_rate: u16,
+    pub max_creator_fee_rate: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub paused: bool,
+    pub min_resolution_delay: i64,
+    pub dispute_window: i64,
+    pub dispute_bond: u64,
+    pub arbiter: Pubkey,
+    pub max_deadline_extension: i64,
+    pub guardian: Pubkey,
+    pub cancellation_cutoff: i64,
+    pub updated_at: i64,
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct LockPool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+    pub signer: Signer<'info>, // Admin or a moderator before the deadline, anyone after it
+}
+
+pub fn handler(ctx: Context<LockPool>) -> Result<()> {
+    let signer_key = ctx.accounts.signer.key();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    if betting_pool.is_cancelled() {
+        return Err(BettingError::PoolCancelled.into());
+    }
+    if betting_pool.status != PoolStatus::Open {
+        return Err(BettingError::InvalidPoolStatus.into());
+    }
+    let clock = Clock::get()?;
+    // Only the admin or a moderator can close betting early, e.g. when the match starts
+    let before_deadline = clock.unix_timestamp < betting_pool.betting_deadline;
+    if before_deadline && signer_key != betting_pool.admin && !betting_pool.is_moderator(&signer_key) {
+        return Err(BettingError::UnauthorizedAdmin.into());
+    }
+    // Betting already stopped at the deadline, so a late lock dates from then
+    let locked_at = clock.unix_timestamp.min(betting_pool.betting_deadline);
+    let betting_pool_key = betting_pool.key();
+    betting_pool.lock(betting_pool_key, locked_at)?;
+    msg!("Betting locked for stream: {}", betting_pool.stream_id);
+    msg!("Result can be declared from: {}", betting_pool.resolution_opens_at()?);
+    Ok(())
+}
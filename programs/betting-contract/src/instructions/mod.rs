@@ -4,10 +4,162 @@ pub mod declare_winner;
 pub mod payout_winners;
 pub mod payout_creator;
 pub mod payout_platform;
+pub mod cancel_pool;
+pub mod claim_refund;
+pub mod initialize_config;
+pub mod update_config;
+pub mod close_losing_bet;
+pub mod close_pool;
+pub mod payout_winners_batch;
+pub mod lock_pool;
+pub mod dispute_result;
+pub mod resolve_dispute;
+pub mod set_moderators;
+pub mod vote_result;
+pub mod set_attestor;
+pub mod declare_winner_with_attestation;
+pub mod resolve_from_oracle;
+pub mod set_moderator;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod set_treasury;
+pub mod update_deadline;
+pub mod set_paused;
+pub mod set_pool_paused;
+pub mod set_cancellation_fee;
+pub mod cancel_bet;
+pub mod set_odds;
+pub mod deposit_liquidity;
+pub mod withdraw_liquidity;
+pub mod seed_market;
+pub mod buy_shares;
+pub mod sell_shares;
+pub mod declare_legacy_result;
+pub mod settle_legacy_bet;
+pub mod close_legacy_pool;
 
 pub use initialize::Initialize;
 pub use place_bet::PlaceBet;
-pub use declare_winner::DeclareWinner; 
+pub use declare_winner::DeclareWinner;
 pub use payout_winners::PayoutWinners;
 pub use payout_creator::PayoutCreator;
-pub use payout_platform::PayoutPlatform;
\ No newline at end of file
+pub use payout_platform::PayoutPlatform;
+pub use cancel_pool::CancelPool;
+pub use claim_refund::ClaimRefund;
+pub use initialize_config::InitializeConfig;
+pub use update_config::UpdateConfig;
+pub use close_losing_bet::CloseLosingBet;
+pub use close_pool::ClosePool;
+pub use payout_winners_batch::PayoutWinnersBatch;
+pub use lock_pool::LockPool;
+pub use dispute_result::DisputeResult;
+pub use resolve_dispute::ResolveDispute;
+pub use set_moderators::SetModerators;
+pub use vote_result::VoteResult;
+pub use set_attestor::SetAttestor;
+pub use declare_winner_with_attestation::DeclareWinnerWithAttestation;
+pub use resolve_from_oracle::ResolveFromOracle;
+pub use set_moderator::SetModerator;
+pub use propose_admin::ProposeAdmin;
+pub use accept_admin::AcceptAdmin;
+pub use set_treasury::SetTreasury;
+pub use update_deadline::UpdateDeadline;
+pub use set_paused::SetPaused;
+pub use set_pool_paused::SetPoolPaused;
+pub use set_cancellation_fee::SetCancellationFee;
+pub use cancel_bet::CancelBet;
+pub use set_odds::SetOdds;
+pub use deposit_liquidity::DepositLiquidity;
+pub use withdraw_liquidity::WithdrawLiquidity;
+pub use seed_market::SeedMarket;
+pub use buy_shares::BuyShares;
+pub use sell_shares::SellShares;
+pub use declare_legacy_result::DeclareLegacyResult;
+pub use settle_legacy_bet::SettleLegacyBet;
+pub use close_legacy_pool::CloseLegacyPool;
+
+// `#[program]` looks up the accounts generated for each instruction at the crate root
+pub(crate) use initialize::__client_accounts_initialize;
+pub(crate) use place_bet::__client_accounts_place_bet;
+pub(crate) use declare_winner::__client_accounts_declare_winner;
+pub(crate) use payout_winners::__client_accounts_payout_winners;
+pub(crate) use payout_creator::__client_accounts_payout_creator;
+pub(crate) use payout_platform::__client_accounts_payout_platform;
+pub(crate) use cancel_pool::__client_accounts_cancel_pool;
+pub(crate) use claim_refund::__client_accounts_claim_refund;
+pub(crate) use initialize_config::__client_accounts_initialize_config;
+pub(crate) use update_config::__client_accounts_update_config;
+pub(crate) use close_losing_bet::__client_accounts_close_losing_bet;
+pub(crate) use close_pool::__client_accounts_close_pool;
+pub(crate) use payout_winners_batch::__client_accounts_payout_winners_batch;
+pub(crate) use lock_pool::__client_accounts_lock_pool;
+pub(crate) use dispute_result::__client_accounts_dispute_result;
+pub(crate) use resolve_dispute::__client_accounts_resolve_dispute;
+pub(crate) use set_moderators::__client_accounts_set_moderators;
+pub(crate) use vote_result::__client_accounts_vote_result;
+pub(crate) use set_attestor::__client_accounts_set_attestor;
+pub(crate) use declare_winner_with_attestation::__client_accounts_declare_winner_with_attestation;
+pub(crate) use resolve_from_oracle::__client_accounts_resolve_from_oracle;
+pub(crate) use set_moderator::__client_accounts_set_moderator;
+pub(crate) use propose_admin::__client_accounts_propose_admin;
+pub(crate) use accept_admin::__client_accounts_accept_admin;
+pub(crate) use set_treasury::__client_accounts_set_treasury;
+pub(crate) use update_deadline::__client_accounts_update_deadline;
+pub(crate) use set_paused::__client_accounts_set_paused;
+pub(crate) use set_pool_paused::__client_accounts_set_pool_paused;
+pub(crate) use set_cancellation_fee::__client_accounts_set_cancellation_fee;
+pub(crate) use cancel_bet::__client_accounts_cancel_bet;
+pub(crate) use set_odds::__client_accounts_set_odds;
+pub(crate) use deposit_liquidity::__client_accounts_deposit_liquidity;
+pub(crate) use withdraw_liquidity::__client_accounts_withdraw_liquidity;
+pub(crate) use seed_market::__client_accounts_seed_market;
+pub(crate) use buy_shares::__client_accounts_buy_shares;
+pub(crate) use sell_shares::__client_accounts_sell_shares;
+pub(crate) use declare_legacy_result::__client_accounts_declare_legacy_result;
+pub(crate) use settle_legacy_bet::__client_accounts_settle_legacy_bet;
+pub(crate) use close_legacy_pool::__client_accounts_close_legacy_pool;
+
+#[cfg(feature = "cpi")]
+mod cpi_accounts {
+    pub(crate) use super::initialize::__cpi_client_accounts_initialize;
+    pub(crate) use super::place_bet::__cpi_client_accounts_place_bet;
+    pub(crate) use super::declare_winner::__cpi_client_accounts_declare_winner;
+    pub(crate) use super::payout_winners::__cpi_client_accounts_payout_winners;
+    pub(crate) use super::payout_creator::__cpi_client_accounts_payout_creator;
+    pub(crate) use super::payout_platform::__cpi_client_accounts_payout_platform;
+    pub(crate) use super::cancel_pool::__cpi_client_accounts_cancel_pool;
+    pub(crate) use super::claim_refund::__cpi_client_accounts_claim_refund;
+    pub(crate) use super::initialize_config::__cpi_client_accounts_initialize_config;
+    pub(crate) use super::update_config::__cpi_client_accounts_update_config;
+    pub(crate) use super::close_losing_bet::__cpi_client_accounts_close_losing_bet;
+    pub(crate) use super::close_pool::__cpi_client_accounts_close_pool;
+    pub(crate) use super::payout_winners_batch::__cpi_client_accounts_payout_winners_batch;
+    pub(crate) use super::lock_pool::__cpi_client_accounts_lock_pool;
+    pub(crate) use super::dispute_result::__cpi_client_accounts_dispute_result;
+    pub(crate) use super::resolve_dispute::__cpi_client_accounts_resolve_dispute;
+    pub(crate) use super::set_moderators::__cpi_client_accounts_set_moderators;
+    pub(crate) use super::vote_result::__cpi_client_accounts_vote_result;
+    pub(crate) use super::set_attestor::__cpi_client_accounts_set_attestor;
+    pub(crate) use super::declare_winner_with_attestation::__cpi_client_accounts_declare_winner_with_attestation;
+    pub(crate) use super::resolve_from_oracle::__cpi_client_accounts_resolve_from_oracle;
+    pub(crate) use super::set_moderator::__cpi_client_accounts_set_moderator;
+    pub(crate) use super::propose_admin::__cpi_client_accounts_propose_admin;
+    pub(crate) use super::accept_admin::__cpi_client_accounts_accept_admin;
+    pub(crate) use super::set_treasury::__cpi_client_accounts_set_treasury;
+    pub(crate) use super::update_deadline::__cpi_client_accounts_update_deadline;
+    pub(crate) use super::set_paused::__cpi_client_accounts_set_paused;
+    pub(crate) use super::set_pool_paused::__cpi_client_accounts_set_pool_paused;
+    pub(crate) use super::set_cancellation_fee::__cpi_client_accounts_set_cancellation_fee;
+    pub(crate) use super::cancel_bet::__cpi_client_accounts_cancel_bet;
+    pub(crate) use super::set_odds::__cpi_client_accounts_set_odds;
+    pub(crate) use super::deposit_liquidity::__cpi_client_accounts_deposit_liquidity;
+    pub(crate) use super::withdraw_liquidity::__cpi_client_accounts_withdraw_liquidity;
+    pub(crate) use super::seed_market::__cpi_client_accounts_seed_market;
+    pub(crate) use super::buy_shares::__cpi_client_accounts_buy_shares;
+    pub(crate) use super::sell_shares::__cpi_client_accounts_sell_shares;
+    pub(crate) use super::declare_legacy_result::__cpi_client_accounts_declare_legacy_result;
+    pub(crate) use super::settle_legacy_bet::__cpi_client_accounts_settle_legacy_bet;
+    pub(crate) use super::close_legacy_pool::__cpi_client_accounts_close_legacy_pool;
+}
+#[cfg(feature = "cpi")]
+pub(crate) use cpi_accounts::*;
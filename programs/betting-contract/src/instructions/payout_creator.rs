@@ -1,42 +1,104 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
 
 #[derive(Accounts)]
 pub struct PayoutCreator<'info> {
     /// The betting pool account
     #[account(
         mut,
-        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
         bump = betting_pool.bump,
         has_one = admin @ BettingError::UnauthorizedAdmin
     )]
     pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     
     /// The admin/creator who will receive the fee
     #[account(mut)]
     pub admin: Signer<'info>,
     
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The admin's token account receiving the fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program,
+    )]
+    pub admin_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    
     /// System program
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<PayoutCreator>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
     let betting_pool = &mut ctx.accounts.betting_pool;
     
-    // Must have declared winner first
-    require!(betting_pool.winner_declared, BettingError::WinnerNotDeclared);
+    // Must have an undisputed result; cancelled pools only pay out refunds
+    betting_pool.require_settleable()?;
     
     // Calculate creator fee (2.5%)
     let creator_fee = betting_pool.calculate_creator_fee();
     require!(creator_fee > 0, BettingError::NothingToPayout);
     
+    // Each fee is paid exactly once
+    require!(betting_pool.creator_fee_paid == 0, BettingError::FeeAlreadyPaid);
+    
     // Transfer creator fee from pool to creator
-    let pool_account = betting_pool.to_account_info();
-    let admin_account = ctx.accounts.admin.to_account_info();
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::pay_from_pool(
+        betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        token,
+        creator_fee,
+    )?;
     
-    **pool_account.try_borrow_mut_lamports()? -= creator_fee;
-    **admin_account.try_borrow_mut_lamports()? += creator_fee;
+    betting_pool.record_creator_fee(creator_fee)?;
+    let betting_pool_key = betting_pool.key();
+    betting_pool.advance_settlement(betting_pool_key)?;
     
     msg!("Creator fee paid: {} lamports ({} SOL)", creator_fee, creator_fee as f64 / 1_000_000_000.0);
     
@@ -1,16 +1,30 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
 
 #[derive(Accounts)]
 pub struct PayoutPlatform<'info> {
     /// The betting pool account
     #[account(
         mut,
-        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
         bump = betting_pool.bump,
     )]
     pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     
     /// CHECK: This is the platform's wallet address
     #[account(
@@ -23,28 +37,85 @@ pub struct PayoutPlatform<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The platform wallet's token account receiving the fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = platform_wallet,
+        token::token_program = token_program,
+    )]
+    pub platform_wallet_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    
     /// System program
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<PayoutPlatform>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
     let betting_pool = &mut ctx.accounts.betting_pool;
     
-    // Must have declared winner first
-    require!(betting_pool.winner_declared, BettingError::WinnerNotDeclared);
+    // Must have an undisputed result; cancelled pools only owe the fees they retained
+    if !betting_pool.is_cancelled() {
+        betting_pool.require_settleable()?;
+    }
     
     // Calculate platform fee (2.5%)
     let platform_fee = betting_pool.calculate_platform_fee();
-    require!(platform_fee > 0, BettingError::NothingToPayout);
+    // Each fee is paid exactly once
+    require!(betting_pool.platform_fee_paid == 0, BettingError::FeeAlreadyPaid);
+    
+    // Cancellation fees go to the platform along with its fee
+    let fees_retained = betting_pool.unpaid_fees_retained();
+    let total_paid = platform_fee
+        .checked_add(fees_retained)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    require!(total_paid > 0, BettingError::NothingToPayout);
     
     // Transfer platform fee from pool to platform wallet
-    let pool_account = betting_pool.to_account_info();
-    let platform_account = ctx.accounts.platform_wallet.to_account_info();
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.platform_wallet_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::pay_from_pool(
+        betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.platform_wallet.to_account_info(),
+        &ctx.accounts.system_program,
+        token,
+        total_paid,
+    )?;
     
-    **pool_account.try_borrow_mut_lamports()? -= platform_fee;
-    **platform_account.try_borrow_mut_lamports()? += platform_fee;
+    betting_pool.record_platform_fee(platform_fee)?;
+    betting_pool.record_fees_retained_paid(fees_retained)?;
+    let betting_pool_key = betting_pool.key();
+    betting_pool.advance_settlement(betting_pool_key)?;
     
     msg!("Platform fee paid: {} lamports ({} SOL)", platform_fee, platform_fee as f64 / 1_000_000_000.0);
+    msg!("Retained fees paid: {} lamports", fees_retained);
     
     Ok(())
 }
@@ -1,131 +1,171 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
 
 #[derive(Accounts)]
 pub struct PayoutWinners<'info> {
   
     #[account(
         mut,
-        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
         bump = betting_pool.bump,
     )]
     pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     
-    /// The winning bet being paid out
+    /// The winning position being paid out
     #[account(
         mut,
         seeds = [
-            b"bet",
+            b"position",
             betting_pool.key().as_ref(),
-            bet.user.as_ref(),
-            &bet.bet_index.to_le_bytes()
+            position.user.as_ref(),
+            &[position.prediction]
         ],
-        bump = bet.bump,
+        bump = position.bump,
         has_one = betting_pool @ BettingError::InvalidBettingPool,
+        close = winner,
     )]
-    pub bet: Account<'info, Bet>,
+    pub position: Account<'info, Position>,
     
     /// CHECK: Winner account is validated against the betting pool's winner field in the handler logic
     #[account(mut)]
     pub winner: UncheckedAccount<'info>,
- 
-    /// CHECK: Platform treasury account is provided by the program and used for fee collection
-    #[account(mut)]
-    pub platform_treasury: UncheckedAccount<'info>,
     
 
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The winner's token account receiving the payout
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = winner,
+        token::token_program = token_program,
+    )]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<PayoutWinners>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
     
     let betting_pool_key = ctx.accounts.betting_pool.key();
-    let bet_key = ctx.accounts.bet.key();
+    let position_key = ctx.accounts.position.key();
     let winner_key = ctx.accounts.winner.key();
     
   
-    if !ctx.accounts.betting_pool.winner_declared {
-        return Err(BettingError::WinnerNotDeclared.into());
-    }
+    // Needs an undisputed result; cancelled pools only pay out refunds
+    ctx.accounts.betting_pool.require_settleable()?;
     
     
-    if ctx.accounts.bet.is_paid_out {
+    if ctx.accounts.position.is_paid_out {
         return Err(BettingError::BetAlreadyPaidOut.into());
     }
     
 
-    if !ctx.accounts.bet.is_winner(ctx.accounts.betting_pool.winning_outcome) {
+    if !ctx.accounts.position.is_winner(ctx.accounts.betting_pool.winning_outcome) {
         return Err(BettingError::BetNotWinner.into());
     }
     
 
-    if ctx.accounts.bet.user != winner_key {
+    if ctx.accounts.position.user != winner_key {
         return Err(ProgramError::InvalidAccountData.into());
     }
     
    
     
-    let total_winning_bets = if ctx.accounts.betting_pool.winning_outcome == 1 {
-        ctx.accounts.betting_pool.player1_bets
-    } else {
-        ctx.accounts.betting_pool.player2_bets
-    };
+    let total_winning_bets = ctx.accounts.betting_pool.winning_bets();
     
     
-    if total_winning_bets == 0 {
+    // Fixed-odds and LMSR positions are owed their own amounts, even a position that sold every share
+    if total_winning_bets == 0 && ctx.accounts.betting_pool.is_parimutuel() {
         return Err(BettingError::NoBetsOnWinningOutcome.into());
     }
     
   
-    let prize_pool = ctx.accounts.betting_pool.prize_pool();
-    let bet_share = (ctx.accounts.bet.amount as u128 * prize_pool as u128) / total_winning_bets as u128;
-    let payout_amount = bet_share as u64;
+    // Parimutuel share: (bet / total on winning outcome) * prize pool, or the fixed-odds payout.
+    // Fees were already taken out of the prize pool, so nothing else is deducted.
+    let bet_share = ctx.accounts.betting_pool.winning_payout(&ctx.accounts.position);
+    let payout_amount = ctx.accounts.betting_pool.next_claim_amount(bet_share);
     
-   
-    let total_platform_fee = ctx.accounts.betting_pool.calculate_platform_fee();
-    let bet_platform_fee = (ctx.accounts.bet.amount as u128 * total_platform_fee as u128) / ctx.accounts.betting_pool.total_pool as u128;
-    let platform_fee_amount = bet_platform_fee as u64;
-    
-    
-    let betting_pool_lamports = ctx.accounts.betting_pool.to_account_info().lamports();
-    if betting_pool_lamports < payout_amount + platform_fee_amount {
-        return Err(BettingError::InsufficientFunds.into());
-    }
     
 
     let stream_id = ctx.accounts.betting_pool.stream_id.clone();
     let winning_outcome = ctx.accounts.betting_pool.winning_outcome;
-    let bet_amount = ctx.accounts.bet.amount;
-    let prediction = ctx.accounts.bet.prediction;
+    let bet_amount = ctx.accounts.position.amount;
+    let prediction = ctx.accounts.position.prediction;
     
     // Transfer winnings to winner
-    **ctx.accounts.betting_pool.to_account_info().try_borrow_mut_lamports()? -= payout_amount;
-    **ctx.accounts.winner.to_account_info().try_borrow_mut_lamports()? += payout_amount;
-    
-    // Transfer platform fee to treasury
-    **ctx.accounts.betting_pool.to_account_info().try_borrow_mut_lamports()? -= platform_fee_amount;
-    **ctx.accounts.platform_treasury.to_account_info().try_borrow_mut_lamports()? += platform_fee_amount;
+    let winner_token = TokenTransferAccounts::for_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.winner_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::pay_from_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.winner.to_account_info(),
+        &ctx.accounts.system_program,
+        winner_token,
+        payout_amount,
+    )?;
+
+    ctx.accounts.betting_pool.record_claim(payout_amount, false)?;
     
-    // Mark this bet as paid out
-    ctx.accounts.bet.is_paid_out = true;
+    // Mark this position as paid out; it is closed and its rent returned to the winner
+    ctx.accounts.position.is_paid_out = true;
+    ctx.accounts.betting_pool.record_position_closed()?;
+    ctx.accounts.betting_pool.advance_settlement(betting_pool_key)?;
     
     let clock = Clock::get()?;
     
     // Emit payout event
     emit!(WinnerPaidOut {
         betting_pool: betting_pool_key,
-        bet: bet_key,
+        position: position_key,
         winner: winner_key,
         stream_id: stream_id.clone(),
         winning_outcome,
         bet_amount,
         payout_amount,
-        platform_fee_amount,
         prediction,
         paid_out_at: clock.unix_timestamp,
     });
@@ -133,10 +173,9 @@ pub fn handler(ctx: Context<PayoutWinners>) -> Result<()> {
     // Log payout details
     msg!("Payout completed for stream: {}", stream_id);
     msg!("Winner: {}", winner_key);
-    msg!("Original bet: {} lamports", bet_amount);
+    msg!("Position stake: {} lamports", bet_amount);
     msg!("Payout amount: {} lamports", payout_amount);
-    msg!("Platform fee: {} lamports", platform_fee_amount);
-    msg!("Winning outcome: Player {}", winning_outcome);
+    msg!("Winning outcome: {}", winning_outcome);
     
     Ok(())
 }
@@ -145,13 +184,12 @@ pub fn handler(ctx: Context<PayoutWinners>) -> Result<()> {
 #[event]
 pub struct WinnerPaidOut {
     pub betting_pool: Pubkey,
-    pub bet: Pubkey,
+    pub position: Pubkey,
     pub winner: Pubkey,
     pub stream_id: String,
     pub winning_outcome: u8,
     pub bet_amount: u64,
     pub payout_amount: u64,
-    pub platform_fee_amount: u64,
     pub prediction: u8,
     pub paid_out_at: i64,
 }
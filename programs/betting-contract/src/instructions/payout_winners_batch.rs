@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+/// Pays out many winning positions in one transaction.
+///
+/// `remaining_accounts` holds one group per position: `(position, winner)` for SOL pools,
+/// `(position, winner, winner_token_account)` for token pools. Every account in a group is writable.
+#[derive(Accounts)]
+pub struct PayoutWinnersBatch<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Keeper cranking the payouts, can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, PayoutWinnersBatch<'info>>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool_key = ctx.accounts.betting_pool.key();
+
+    ctx.accounts.betting_pool.require_settleable()?;
+    // Fixed-odds and LMSR positions are owed their own amounts, even a position that sold every share
+    if ctx.accounts.betting_pool.winning_bets() == 0 && ctx.accounts.betting_pool.is_parimutuel() {
+        return Err(BettingError::NoBetsOnWinningOutcome.into());
+    }
+
+    let winning_outcome = ctx.accounts.betting_pool.winning_outcome;
+    let group_size = if ctx.accounts.betting_pool.is_token_pool() { 3 } else { 2 };
+    let groups = ctx.remaining_accounts.chunks_exact(group_size);
+    require!(groups.remainder().is_empty(), BettingError::InvalidBatchAccounts);
+
+    let mut paid_count: u32 = 0;
+    let mut skipped_count: u32 = 0;
+    let mut total_paid: u64 = 0;
+
+    for group in groups {
+        let position_info = &group[0];
+        let winner_info = &group[1];
+
+        // Only the winner's position on the winning outcome of this pool belongs in the group
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[
+                b"position",
+                betting_pool_key.as_ref(),
+                winner_info.key().as_ref(),
+                &[winning_outcome],
+            ],
+            &crate::ID,
+        );
+        require_keys_eq!(expected_key, position_info.key(), BettingError::InvalidBatchAccounts);
+
+        // Paid positions are closed, so an empty account at that address was already settled
+        if position_info.data_is_empty() {
+            skipped_count += 1;
+            continue;
+        }
+        require_keys_eq!(*position_info.owner, crate::ID, BettingError::InvalidBatchAccounts);
+
+        // The address pins the pool, winner and outcome, leaving the account type to check
+        let position = Account::<Position>::try_from(position_info)?;
+        if position.is_paid_out {
+            skipped_count += 1;
+            continue;
+        }
+
+        let bet_share = ctx.accounts.betting_pool.winning_payout(&position);
+        let payout_amount = ctx.accounts.betting_pool.next_claim_amount(bet_share);
+
+        let winner_token_account = match group.get(2) {
+            Some(info) => Some(InterfaceAccount::<TokenAccount>::try_from(info)?),
+            None => None,
+        };
+        let winner_token = TokenTransferAccounts::for_pool(
+            &ctx.accounts.betting_pool,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_vault,
+            &winner_token_account,
+            &ctx.accounts.token_program,
+        )?;
+        transfers::pay_from_pool(
+            &ctx.accounts.betting_pool,
+            &ctx.accounts.vault,
+            winner_info,
+            &ctx.accounts.system_program,
+            winner_token,
+            payout_amount,
+        )?;
+
+        ctx.accounts.betting_pool.record_claim(payout_amount, false)?;
+        ctx.accounts.betting_pool.record_position_closed()?;
+
+        // Close the settled position and return its rent to the winner
+        position.close(winner_info.clone())?;
+
+        paid_count += 1;
+        total_paid = total_paid
+            .checked_add(payout_amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+    }
+
+    ctx.accounts.betting_pool.advance_settlement(betting_pool_key)?;
+
+    let clock = Clock::get()?;
+    let stream_id = ctx.accounts.betting_pool.stream_id.clone();
+
+    emit!(WinnersBatchPaidOut {
+        betting_pool: betting_pool_key,
+        stream_id: stream_id.clone(),
+        winning_outcome,
+        paid_count,
+        skipped_count,
+        total_paid,
+        paid_out_at: clock.unix_timestamp,
+    });
+
+    msg!("Batch payout for stream: {}", stream_id);
+    msg!("Paid {} positions, skipped {} already paid", paid_count, skipped_count);
+    msg!("Total paid: {} lamports", total_paid);
+
+    Ok(())
+}
+
+// Event emitted once per batch payout
+#[event]
+pub struct WinnersBatchPaidOut {
+    pub betting_pool: Pubkey,
+    pub stream_id: String,
+    pub winning_outcome: u8,
+    pub paid_count: u32,
+    pub skipped_count: u32,
+    pub total_paid: u64,
+    pub paid_out_at: i64,
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::constants::ODDS_PRECISION;
 use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
 
 #[derive(Accounts)]
 #[instruction(prediction: u8, amount: u64)]
@@ -9,37 +11,78 @@ pub struct PlaceBet<'info> {
    
     #[account(
         mut,
-        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
         bump = betting_pool.bump,
     )]
     pub betting_pool: Account<'info, BettingPool>,
     
-  
+    /// The user's position on the predicted outcome, created by their first bet on it
     #[account(
-        init,
+        init_if_needed,
         payer = user,
-        space = Bet::LEN,
+        space = Position::LEN,
         seeds = [
-            b"bet",
+            b"position",
             betting_pool.key().as_ref(),
             user.key().as_ref(),
-            &betting_pool.total_bet_count().to_le_bytes()
+            &[prediction]
         ],
         bump
     )]
-    pub bet: Account<'info, Bet>,
+    pub position: Account<'info, Position>,
     
    
     #[account(mut)]
     pub user: Signer<'info>,
     
+    /// Program config supplying bet limits and the pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The user's token account the bet is paid from
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
  
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()> {
     
-    if prediction != 1 && prediction != 2 {
+    if !ctx.accounts.betting_pool.is_valid_outcome(prediction) {
         return Err(BettingError::InvalidPrediction.into());
     }
     
@@ -48,6 +91,18 @@ pub fn handler(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()
         return Err(BettingError::InsufficientFunds.into());
     }
     
+    let config = &ctx.accounts.config;
+    ctx.accounts.betting_pool.require_not_paused(config)?;
+    
+    // LMSR pools trade shares through buy_shares instead of taking stakes
+    require!(!ctx.accounts.betting_pool.is_lmsr(), BettingError::InvalidMarketType);
+    
+    // Bet limits are in lamports, so they only apply to SOL pools
+    if !ctx.accounts.betting_pool.is_token_pool() {
+        require!(amount >= config.min_bet, BettingError::BetTooSmall);
+        require!(amount <= config.max_bet, BettingError::BetTooLarge);
+    }
+    
   
     if !ctx.accounts.betting_pool.is_betting_open() {
         return Err(BettingError::BettingClosed.into());
@@ -58,87 +113,129 @@ pub fn handler(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()
     
     let user_key = ctx.accounts.user.key();
     let betting_pool_key = ctx.accounts.betting_pool.key();
-    let bet_key = ctx.accounts.bet.key();
-    let betting_pool_account_info = ctx.accounts.betting_pool.to_account_info();
-    
-  
-    let transfer_instruction = system_program::Transfer {
-        from: ctx.accounts.user.to_account_info(),
-        to: betting_pool_account_info,
-    };
+    let position_key = ctx.accounts.position.key();
+    let is_new_position = ctx.accounts.position.is_new();
     
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        transfer_instruction,
-    );
+    let token = TokenTransferAccounts::for_pool(
+        &ctx.accounts.betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+    )?;
     
-    system_program::transfer(cpi_context, amount)?;
+    // The stake is what the pool actually received (Token-2022 transfer fees are withheld)
+    let amount = transfers::deposit_to_pool(
+        &ctx.accounts.vault,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        token,
+        amount,
+    )?;
+    if amount == 0 {
+        return Err(BettingError::InsufficientFunds.into());
+    }
     
    
     let betting_pool = &mut ctx.accounts.betting_pool;
     
-    // Store the current bet index before incrementing counters
+    // Order of the bet in the pool, kept for display only
     let bet_index = betting_pool.total_bet_count();
+    
+    // A fixed-odds bet locks in its payout at the odds offered right now
+    let (odds, potential_payout) = if betting_pool.is_fixed_odds() {
+        let odds = betting_pool.outcome(prediction).map_or(0, |outcome| outcome.odds);
+        (odds, betting_pool.fixed_odds_payout(prediction, amount)?)
+    } else {
+        (0, 0)
+    };
    
     betting_pool.total_pool = betting_pool.total_pool
         .checked_add(amount)
         .ok_or(BettingError::ArithmeticOverflow)?;
     
-    if prediction == 1 {
-        betting_pool.player1_bets = betting_pool.player1_bets
-            .checked_add(amount)
-            .ok_or(BettingError::ArithmeticOverflow)?;
-        betting_pool.player1_bet_count = betting_pool.player1_bet_count
-            .checked_add(1)
-            .ok_or(BettingError::ArithmeticOverflow)?;
-    } else {
-        betting_pool.player2_bets = betting_pool.player2_bets
-            .checked_add(amount)
-            .ok_or(BettingError::ArithmeticOverflow)?;
-        betting_pool.player2_bet_count = betting_pool.player2_bet_count
+    let outcome = betting_pool
+        .outcome_mut(prediction)
+        .ok_or(BettingError::InvalidPrediction)?;
+    outcome.total_bets = outcome.total_bets
+        .checked_add(amount)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    outcome.bet_count = outcome.bet_count
+        .checked_add(1)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    if is_new_position {
+        outcome.position_count = outcome.position_count
             .checked_add(1)
             .ok_or(BettingError::ArithmeticOverflow)?;
     }
+    outcome.liability = outcome.liability
+        .checked_add(potential_payout)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    if betting_pool.is_fixed_odds() {
+        betting_pool.require_liability_covered()?;
+    }
     
 
-    let bet = &mut ctx.accounts.bet;
-    bet.user = user_key;
-    bet.betting_pool = betting_pool_key;
-    bet.amount = amount;
-    bet.prediction = prediction;
-    bet.timestamp = clock.unix_timestamp;
-    bet.is_paid_out = false;
-    bet.bet_index = bet_index;
-    bet.bump = ctx.bumps.bet;
+    let position = &mut ctx.accounts.position;
+    if is_new_position {
+        position.user = user_key;
+        position.betting_pool = betting_pool_key;
+        position.prediction = prediction;
+        position.first_bet_at = clock.unix_timestamp;
+        position.is_paid_out = false;
+        position.bet_index = bet_index;
+        position.bump = ctx.bumps.position;
+    }
+    // Top up the stake
+    position.amount = position.amount
+        .checked_add(amount)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.bet_count = position.bet_count
+        .checked_add(1)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.potential_payout = position.potential_payout
+        .checked_add(potential_payout)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    position.last_bet_at = clock.unix_timestamp;
+    let position_amount = position.amount;
+    let position_bet_count = position.bet_count;
     
   
     let total_pool = betting_pool.total_pool;
-    let player1_bets = betting_pool.player1_bets;
-    let player2_bets = betting_pool.player2_bets;
+    let outcome_bets: Vec<u64> = betting_pool.outcomes.iter().map(|outcome| outcome.total_bets).collect();
+    let outcome_label = betting_pool.outcomes[prediction as usize - 1].label.clone();
     let stream_id = betting_pool.stream_id.clone();
     
     
     emit!(BetPlaced {
-        bet: bet_key,
+        position: position_key,
         user: user_key,
         betting_pool: betting_pool_key,
         stream_id: stream_id.clone(),
         prediction,
         amount,
+        odds,
+        bet_index,
+        position_amount,
+        position_bet_count,
         total_pool,
-        player1_bets,
-        player2_bets,
+        outcome_bets: outcome_bets.clone(),
         timestamp: clock.unix_timestamp,
     });
     
    
     msg!("Bet placed on stream: {}", stream_id);
     msg!("User: {}", user_key);
-    msg!("Prediction: Player {}", prediction);
+    msg!("Prediction: {} ({})", prediction, outcome_label);
     msg!("Amount: {} lamports", amount);
+    if odds > 0 {
+        msg!("Odds: {}, pays {} lamports if it wins", odds as f64 / ODDS_PRECISION as f64, potential_payout);
+    }
+    msg!("Position: {} lamports over {} bets", position_amount, position_bet_count);
     msg!("Total pool: {} lamports", total_pool);
-    msg!("Player 1 total: {} lamports", player1_bets);
-    msg!("Player 2 total: {} lamports", player2_bets);
+    for (index, bets) in outcome_bets.iter().enumerate() {
+        msg!("Outcome {} total: {} lamports", index + 1, bets);
+    }
     
     Ok(())
 }
@@ -146,14 +243,17 @@ pub fn handler(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()
 
 #[event]
 pub struct BetPlaced {
-    pub bet: Pubkey,
+    pub position: Pubkey,
     pub user: Pubkey,
     pub betting_pool: Pubkey,
     pub stream_id: String,
     pub prediction: u8,
     pub amount: u64,
+    pub odds: u32,
+    pub bet_index: u32,
+    pub position_amount: u64,
+    pub position_bet_count: u32,
     pub total_pool: u64,
-    pub player1_bets: u64,
-    pub player2_bets: u64,
+    pub outcome_bets: Vec<u64>,
     pub timestamp: i64,
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // The admin closes the pool once it settles, so the role can move at any point.
+    // Proposing again replaces an earlier proposal that was never accepted.
+    betting_pool.pending_admin = Some(new_admin);
+
+    let clock = Clock::get()?;
+
+    emit!(AdminProposed {
+        betting_pool: betting_pool.key(),
+        admin: betting_pool.admin,
+        pending_admin: new_admin,
+        proposed_at: clock.unix_timestamp,
+    });
+
+    msg!("Admin proposed for stream: {}", betting_pool.stream_id);
+    msg!("Pending admin: {}", new_admin);
+
+    Ok(())
+}
+
+// Event emitted when the admin proposes a successor
+#[event]
+pub struct AdminProposed {
+    pub betting_pool: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub proposed_at: i64,
+}
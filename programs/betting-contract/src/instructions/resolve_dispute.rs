@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::{OUTCOME_DRAW, OUTCOME_VOID};
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = arbiter @ BettingError::UnauthorizedArbiter,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Arbiter named in the program config
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// The bettor's dispute, closed back to the challenger once ruled on.
+    /// Leave out when moderators' conflicting votes disputed the pool
+    #[account(
+        mut,
+        seeds = [b"dispute", betting_pool.key().as_ref()],
+        bump = dispute.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = challenger,
+        close = challenger,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    /// CHECK: Validated against `dispute.challenger` by the `has_one` constraint above, receives the rent
+    #[account(mut)]
+    pub challenger: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated against the pool's platform treasury, receives a slashed bond
+    #[account(
+        mut,
+        constraint = platform_treasury.key() == betting_pool.platform_treasury @ BettingError::InvalidPlatformWallet
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<ResolveDispute>, winning_outcome: u8) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    require!(betting_pool.status == PoolStatus::Disputed, BettingError::InvalidPoolStatus);
+
+    let is_refund_outcome = winning_outcome == OUTCOME_VOID || winning_outcome == OUTCOME_DRAW;
+    if !betting_pool.is_valid_outcome(winning_outcome) && !is_refund_outcome {
+        return Err(BettingError::InvalidWinningOutcome.into());
+    }
+
+    // Bettors can only challenge a declared result, a moderator deadlock has none yet
+    let has_declared_result = betting_pool.dispute_deadline != 0;
+    require!(
+        ctx.accounts.dispute.is_some() == has_declared_result,
+        BettingError::MissingDisputeAccount
+    );
+
+    let betting_pool_key = betting_pool.key();
+    let (challenger, disputed_outcome, bond) = match &ctx.accounts.dispute {
+        Some(dispute) => (Some(dispute.challenger), Some(dispute.disputed_outcome), dispute.bond),
+        None => (None, None, 0),
+    };
+    let upheld = disputed_outcome != Some(winning_outcome);
+
+    betting_pool.set_status(betting_pool_key, PoolStatus::Resolved)?;
+    if upheld {
+        // Nothing has been paid while the pool was disputed, so the split is simply recomputed
+        betting_pool.apply_result(winning_outcome)?;
+    } else if let Some(dispute) = &ctx.accounts.dispute {
+        // A rejected challenge forfeits its bond to the platform
+        if bond > 0 {
+            dispute.sub_lamports(bond)?;
+            ctx.accounts.platform_treasury.add_lamports(bond)?;
+        }
+    }
+
+    // The arbiter's ruling is final, so payouts open immediately
+    let clock = Clock::get()?;
+    betting_pool.dispute_deadline = clock.unix_timestamp;
+    if betting_pool.is_payout_complete() {
+        betting_pool.set_status(betting_pool_key, PoolStatus::Settled)?;
+    }
+
+    emit!(DisputeResolved {
+        betting_pool: betting_pool_key,
+        challenger,
+        arbiter: ctx.accounts.arbiter.key(),
+        disputed_outcome,
+        winning_outcome,
+        upheld,
+        bond,
+        resolved_at: clock.unix_timestamp,
+    });
+
+    msg!("Dispute resolved for stream: {}", betting_pool.stream_id);
+    msg!("Winning outcome: {}", winning_outcome);
+    if upheld {
+        msg!("Challenge upheld, bond of {} lamports returned", bond);
+    } else {
+        msg!("Challenge rejected, bond of {} lamports slashed", bond);
+    }
+
+    Ok(())
+}
+
+// Event emitted when the arbiter rules on a dispute
+#[event]
+pub struct DisputeResolved {
+    pub betting_pool: Pubkey,
+    pub challenger: Option<Pubkey>,
+    pub arbiter: Pubkey,
+    pub disputed_outcome: Option<u8>,
+    pub winning_outcome: u8,
+    pub upheld: bool,
+    pub bond: u64,
+    pub resolved_at: i64,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+use super::declare_winner::{declare_result, require_resolvable};
+
+/// Reads the result from the pool's resolver account and declares it. Anyone can call it.
+#[derive(Accounts)]
+pub struct ResolveFromOracle<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Checked against the pool's resolver config, key and owner, in the handler
+    pub resolver_account: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ResolveFromOracle>) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    let resolver = betting_pool.resolver.ok_or(BettingError::ResolverNotSet)?;
+
+    let resolver_account = &ctx.accounts.resolver_account;
+    require_keys_eq!(resolver_account.key(), resolver.account, BettingError::InvalidResolverAccount);
+    require_keys_eq!(*resolver_account.owner, resolver.owner, BettingError::InvalidResolverAccount);
+
+    // A value feed reading only counts if taken after betting closed.
+    // An open pool past its deadline is locked as of the deadline by require_resolvable
+    let winning_outcome = {
+        let data = resolver_account.try_borrow_data()?;
+        let locked_at = if betting_pool.status == PoolStatus::Open {
+            betting_pool.betting_deadline
+        } else {
+            betting_pool.locked_at
+        };
+        resolver.read_outcome(&data, locked_at)?
+    }
+    .ok_or(BettingError::ResultNotAvailable)?;
+
+    require_resolvable(betting_pool, winning_outcome)?;
+
+    msg!("Result read from resolver: {}", resolver.account);
+    declare_result(betting_pool, winning_outcome)
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::pricing::lmsr;
+use crate::transfers::{self, TokenTransferAccounts};
+
+/// Sets an LMSR pool's liquidity parameter `b` and deposits the market maker's worst-case loss,
+/// `b * ln(n)`, from the admin. A larger `b` moves prices less per share traded.
+/// The deposit comes back with `withdraw_liquidity` once the pool settles.
+#[derive(Accounts)]
+pub struct SeedMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin's token account the seed liquidity is paid from
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program,
+    )]
+    pub admin_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SeedMarket>, liquidity_parameter: u64) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &ctx.accounts.betting_pool;
+    require!(betting_pool.is_lmsr(), BettingError::InvalidMarketType);
+    require!(betting_pool.is_betting_open(), BettingError::BettingClosed);
+    require!(betting_pool.liquidity_parameter == 0, BettingError::MarketAlreadySeeded);
+
+    let seed_amount = lmsr::max_loss(betting_pool.outcomes.len(), liquidity_parameter)
+        .ok_or(BettingError::InvalidLiquidityParameter)?;
+
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    // Every payout relies on the full worst-case loss being in the vault. LMSR pools never
+    // use a transfer-fee mint, so the whole seed arrives
+    let received = transfers::deposit_to_pool(
+        &ctx.accounts.vault,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        token,
+        seed_amount,
+    )?;
+    require!(received >= seed_amount, BettingError::InsufficientFunds);
+
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    betting_pool.liquidity_parameter = liquidity_parameter;
+    betting_pool.liquidity = received;
+
+    let clock = Clock::get()?;
+
+    emit!(MarketSeeded {
+        betting_pool: betting_pool.key(),
+        admin: ctx.accounts.admin.key(),
+        liquidity_parameter,
+        liquidity: received,
+        seeded_at: clock.unix_timestamp,
+    });
+
+    msg!("Market seeded for stream: {}", betting_pool.stream_id);
+    msg!("Liquidity parameter: {}, deposited: {} lamports", liquidity_parameter, received);
+
+    Ok(())
+}
+
+// Event emitted when an LMSR pool's market is seeded
+#[event]
+pub struct MarketSeeded {
+    pub betting_pool: Pubkey,
+    pub admin: Pubkey,
+    pub liquidity_parameter: u64,
+    pub liquidity: u64,
+    pub seeded_at: i64,
+}
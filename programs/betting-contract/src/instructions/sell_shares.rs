@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::pricing::lmsr;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+pub struct SellShares<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The position selling shares
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            betting_pool.key().as_ref(),
+            user.key().as_ref(),
+            &[position.prediction]
+        ],
+        bump = position.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = user,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user's token account receiving the proceeds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SellShares>, shares: u64, min_proceeds: u64) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let betting_pool = &ctx.accounts.betting_pool;
+    require!(betting_pool.is_lmsr(), BettingError::InvalidMarketType);
+    require!(betting_pool.is_betting_open(), BettingError::BettingClosed);
+
+    let position = &ctx.accounts.position;
+    let prediction = position.prediction;
+    let held = position.potential_payout;
+    require!(shares > 0 && shares <= held, BettingError::InsufficientShares);
+
+    let proceeds = lmsr::sell_proceeds(
+        &betting_pool.outcome_shares(),
+        prediction as usize - 1,
+        shares,
+        betting_pool.liquidity_parameter,
+    )
+    .ok_or(BettingError::ArithmeticOverflow)?;
+    require!(proceeds >= min_proceeds, BettingError::SlippageExceeded);
+
+    // The shares sold take their part of the position's cost basis with them
+    let cost_basis = if shares == held {
+        position.amount
+    } else {
+        (position.amount as u128 * shares as u128 / held as u128) as u64
+    };
+
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if proceeds > 0 {
+        transfers::pay_from_pool(
+            betting_pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.system_program,
+            token,
+            proceeds,
+        )?;
+    }
+
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    betting_pool.total_pool = betting_pool.total_pool
+        .checked_sub(cost_basis)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    let outcome = betting_pool
+        .outcome_mut(prediction)
+        .ok_or(BettingError::InvalidPrediction)?;
+    outcome.total_bets = outcome.total_bets
+        .checked_sub(cost_basis)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    outcome.liability = outcome.liability
+        .checked_sub(shares)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+
+    let position = &mut ctx.accounts.position;
+    position.amount -= cost_basis;
+    position.potential_payout -= shares;
+
+    let prices = lmsr::prices(&betting_pool.outcome_shares(), betting_pool.liquidity_parameter)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    let clock = Clock::get()?;
+
+    emit!(SharesSold {
+        betting_pool: betting_pool.key(),
+        position: position.key(),
+        user: ctx.accounts.user.key(),
+        outcome: prediction,
+        shares,
+        proceeds,
+        position_shares: position.potential_payout,
+        prices,
+        sold_at: clock.unix_timestamp,
+    });
+
+    msg!("Shares sold on stream: {}", betting_pool.stream_id);
+    msg!("User: {}", ctx.accounts.user.key());
+    msg!("Sold {} shares of outcome {} for {} lamports", shares, prediction, proceeds);
+
+    Ok(())
+}
+
+// Event emitted when a user sells LMSR shares back to the market.
+// Prices are scaled by `lmsr::PRICE_SCALE`
+#[event]
+pub struct SharesSold {
+    pub betting_pool: Pubkey,
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub shares: u64,
+    pub proceeds: u64,
+    pub position_shares: u64,
+    pub prices: Vec<u64>,
+    pub sold_at: i64,
+}
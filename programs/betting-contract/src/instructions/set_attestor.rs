@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetAttestor<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetAttestor>, attestor: Option<Pubkey>) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // Frozen once betting closes, so the admin cannot name a key of their own to sign the result
+    require!(betting_pool.status == PoolStatus::Open, BettingError::InvalidPoolStatus);
+
+    betting_pool.attestor = attestor;
+
+    let clock = Clock::get()?;
+
+    emit!(AttestorUpdated {
+        betting_pool: betting_pool.key(),
+        attestor,
+        updated_at: clock.unix_timestamp,
+    });
+
+    msg!("Attestor updated for stream: {}", betting_pool.stream_id);
+    match attestor {
+        Some(attestor) => msg!("Attestor: {}", attestor),
+        None => msg!("Attestor removed"),
+    }
+
+    Ok(())
+}
+
+// Event emitted when a pool's attestor changes
+#[event]
+pub struct AttestorUpdated {
+    pub betting_pool: Pubkey,
+    pub attestor: Option<Pubkey>,
+    pub updated_at: i64,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::MAX_FEE_RATE;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetCancellationFee<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetCancellationFee>, cancellation_fee_rate: u16) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // Bettors must know the fee before they bet, so it is fixed once the first bet is in
+    require!(betting_pool.status == PoolStatus::Open, BettingError::InvalidPoolStatus);
+    require!(betting_pool.total_bet_count() == 0, BettingError::BetsAlreadyPlaced);
+    require!(cancellation_fee_rate <= MAX_FEE_RATE, BettingError::InvalidFeeRate);
+
+    betting_pool.cancellation_fee_rate = cancellation_fee_rate;
+
+    let clock = Clock::get()?;
+
+    emit!(CancellationFeeUpdated {
+        betting_pool: betting_pool.key(),
+        cancellation_fee_rate,
+        updated_at: clock.unix_timestamp,
+    });
+
+    msg!("Cancellation fee for stream {}: {} bps", betting_pool.stream_id, cancellation_fee_rate);
+
+    Ok(())
+}
+
+// Event emitted when a pool's cancellation fee changes
+#[event]
+pub struct CancellationFeeUpdated {
+    pub betting_pool: Pubkey,
+    pub cancellation_fee_rate: u16,
+    pub updated_at: i64,
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetModerator<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetModerator>, moderator: Pubkey) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // The moderator only acts until the result is in
+    require!(
+        matches!(betting_pool.status, PoolStatus::Open | PoolStatus::Locked),
+        BettingError::InvalidPoolStatus
+    );
+
+    let previous_moderator = betting_pool.moderator;
+    betting_pool.moderator = moderator;
+
+    let clock = Clock::get()?;
+
+    emit!(ModeratorChanged {
+        betting_pool: betting_pool.key(),
+        previous_moderator,
+        moderator,
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Moderator changed for stream: {}", betting_pool.stream_id);
+    msg!("New moderator: {}", moderator);
+
+    Ok(())
+}
+
+// Event emitted when a pool's moderator is replaced
+#[event]
+pub struct ModeratorChanged {
+    pub betting_pool: Pubkey,
+    pub previous_moderator: Pubkey,
+    pub moderator: Pubkey,
+    pub changed_at: i64,
+}
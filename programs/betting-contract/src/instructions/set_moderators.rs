@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::MAX_MODERATORS;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetModerators<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetModerators>, moderators: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // Voting starts once the pool locks, so the set is fixed from then on
+    require!(betting_pool.status == PoolStatus::Open, BettingError::InvalidPoolStatus);
+
+    // An empty set with a zero threshold goes back to a single moderator
+    require!(moderators.len() <= MAX_MODERATORS as usize, BettingError::InvalidModeratorSet);
+    if moderators.is_empty() {
+        require!(threshold == 0, BettingError::InvalidModeratorSet);
+    } else {
+        require!(
+            threshold >= 1 && threshold as usize <= moderators.len(),
+            BettingError::InvalidModeratorSet
+        );
+    }
+    for (index, moderator) in moderators.iter().enumerate() {
+        require!(!moderators[..index].contains(moderator), BettingError::InvalidModeratorSet);
+    }
+
+    betting_pool.moderators = moderators;
+    betting_pool.moderator_threshold = threshold;
+
+    let clock = Clock::get()?;
+
+    emit!(ModeratorsUpdated {
+        betting_pool: betting_pool.key(),
+        moderators: betting_pool.moderators.clone(),
+        threshold,
+        updated_at: clock.unix_timestamp,
+    });
+
+    msg!("Moderator set updated for stream: {}", betting_pool.stream_id);
+    msg!("{} of {} votes needed", threshold, betting_pool.moderators.len());
+
+    Ok(())
+}
+
+// Event emitted when a pool's moderator set changes
+#[event]
+pub struct ModeratorsUpdated {
+    pub betting_pool: Pubkey,
+    pub moderators: Vec<Pubkey>,
+    pub threshold: u8,
+    pub updated_at: i64,
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::ODDS_PRECISION;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetOdds<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetOdds>, odds: Vec<u32>) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    require!(betting_pool.is_fixed_odds(), BettingError::InvalidMarketType);
+    require!(betting_pool.is_betting_open(), BettingError::BettingClosed);
+
+    // One price per outcome, each paying back more than the stake.
+    // Bets already placed keep the odds they were placed at
+    require!(odds.len() == betting_pool.outcomes.len(), BettingError::InvalidOdds);
+    require!(odds.iter().all(|odds| *odds > ODDS_PRECISION), BettingError::InvalidOdds);
+
+    for (outcome, odds) in betting_pool.outcomes.iter_mut().zip(&odds) {
+        outcome.odds = *odds;
+    }
+
+    let clock = Clock::get()?;
+
+    emit!(OddsUpdated {
+        betting_pool: betting_pool.key(),
+        odds: odds.clone(),
+        updated_at: clock.unix_timestamp,
+    });
+
+    msg!("Odds updated for stream: {}", betting_pool.stream_id);
+    for (index, odds) in odds.iter().enumerate() {
+        msg!("Outcome {} odds: {}", index + 1, *odds as f64 / ODDS_PRECISION as f64);
+    }
+
+    Ok(())
+}
+
+// Event emitted when a fixed-odds pool's prices change
+#[event]
+pub struct OddsUpdated {
+    pub betting_pool: Pubkey,
+    pub odds: Vec<u32>,
+    pub updated_at: i64,
+}
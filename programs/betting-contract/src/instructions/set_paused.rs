@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian @ BettingError::UnauthorizedGuardian,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Emergency key named in the program config
+    pub guardian: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    let clock = Clock::get()?;
+
+    emit!(ProgramPauseChanged {
+        config: config.key(),
+        paused,
+        changed_by: ctx.accounts.guardian.key(),
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Program {}", if paused { "paused" } else { "unpaused" });
+
+    Ok(())
+}
+
+// Event emitted when the guardian pauses or unpauses the program
+#[event]
+pub struct ProgramPauseChanged {
+    pub config: Pubkey,
+    pub paused: bool,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetPoolPaused<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian @ BettingError::UnauthorizedGuardian,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Emergency key named in the program config
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+}
+
+pub fn handler(ctx: Context<SetPoolPaused>, paused: bool) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    betting_pool.paused = paused;
+
+    let clock = Clock::get()?;
+
+    emit!(PoolPauseChanged {
+        betting_pool: betting_pool.key(),
+        paused,
+        changed_by: ctx.accounts.guardian.key(),
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Betting pool {} for stream: {}", if paused { "paused" } else { "unpaused" }, betting_pool.stream_id);
+
+    Ok(())
+}
+
+// Event emitted when the guardian pauses or unpauses a pool
+#[event]
+pub struct PoolPauseChanged {
+    pub betting_pool: Pubkey,
+    pub paused: bool,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ BettingError::UnauthorizedConfigAuthority,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The platform authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+}
+
+pub fn handler(ctx: Context<SetTreasury>, platform_treasury: Pubkey) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    // Once the platform fee has gone out the treasury has been paid, only dust is left
+    require!(
+        betting_pool.platform_fee_paid == 0 && betting_pool.fees_retained_paid == 0,
+        BettingError::FeeAlreadyPaid
+    );
+
+    let previous_treasury = betting_pool.platform_treasury;
+    betting_pool.platform_treasury = platform_treasury;
+
+    let clock = Clock::get()?;
+
+    emit!(TreasuryChanged {
+        betting_pool: betting_pool.key(),
+        previous_treasury,
+        platform_treasury,
+        changed_by: ctx.accounts.authority.key(),
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Platform treasury changed for stream: {}", betting_pool.stream_id);
+    msg!("New treasury: {}", platform_treasury);
+
+    Ok(())
+}
+
+// Event emitted when a pool's platform treasury is replaced
+#[event]
+pub struct TreasuryChanged {
+    pub betting_pool: Pubkey,
+    pub previous_treasury: Pubkey,
+    pub platform_treasury: Pubkey,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
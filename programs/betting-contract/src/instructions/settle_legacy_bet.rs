@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers;
+
+#[derive(Accounts)]
+pub struct SettleLegacyBet<'info> {
+    /// A pool created before pools were namespaced by creator
+    #[account(
+        mut,
+        seeds = [b"betting_pool", betting_pool.stream_id.as_bytes()],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, LegacyBettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The bet being settled, closed once it is
+    #[account(
+        mut,
+        seeds = [
+            b"bet",
+            betting_pool.key().as_ref(),
+            bet.user.as_ref(),
+            &bet.bet_index.to_le_bytes(),
+        ],
+        bump = bet.bump,
+        has_one = betting_pool @ BettingError::InvalidBettingPool,
+        has_one = user,
+        close = user,
+    )]
+    pub bet: Account<'info, LegacyBet>,
+
+    /// CHECK: Validated against `bet.user` by the `has_one` constraint above, receives the payout and the rent
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// Anyone can settle a legacy bet, the funds always go to its owner
+    pub payer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SettleLegacyBet>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, BettingError::ProgramPaused);
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    require!(betting_pool.winner_declared, BettingError::WinnerNotDeclared);
+
+    let bet = &ctx.accounts.bet;
+    let amount = betting_pool.settlement_amount(bet)?;
+    if amount > 0 {
+        transfers::pay_from_legacy_pool(betting_pool, &ctx.accounts.user.to_account_info(), amount)?;
+    }
+    betting_pool.record_bet_settled(bet.prediction)?;
+
+    let clock = Clock::get()?;
+
+    emit!(LegacyBetSettled {
+        betting_pool: betting_pool.key(),
+        bet: bet.key(),
+        user: bet.user,
+        prediction: bet.prediction,
+        bet_amount: bet.amount,
+        amount_paid: amount,
+        settled_at: clock.unix_timestamp,
+    });
+
+    msg!("Legacy bet settled for stream: {}", betting_pool.stream_id);
+    msg!("User: {}", bet.user);
+    msg!("Paid: {} lamports", amount);
+
+    Ok(())
+}
+
+// Event emitted when a legacy bet is settled and closed
+#[event]
+pub struct LegacyBetSettled {
+    pub betting_pool: Pubkey,
+    pub bet: Pubkey,
+    pub user: Pubkey,
+    pub prediction: u8,
+    pub bet_amount: u64,
+    pub amount_paid: u64,
+    pub settled_at: i64,
+}
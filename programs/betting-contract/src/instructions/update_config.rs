@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+use super::initialize_config::ConfigUpdated;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ BettingError::UnauthorizedConfigAuthority,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    
+    /// The current platform authority
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(&params)?;
+    
+    let clock = Clock::get()?;
+    
+    emit!(ConfigUpdated {
+        config: config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        authority: config.authority,
+        platform_treasury: config.platform_treasury,
+        platform_fee_rate: config.platform_fee_rate,
+        default_creator_fee_rate: config.default_creator_fee_rate,
+        max_creator_fee_rate: config.max_creator_fee_rate,
+        min_bet: config.min_bet,
+        max_bet: config.max_bet,
+        paused: config.paused,
+        min_resolution_delay: config.min_resolution_delay,
+        dispute_window: config.dispute_window,
+        dispute_bond: config.dispute_bond,
+        arbiter: config.arbiter,
+        max_deadline_extension: config.max_deadline_extension,
+        guardian: config.guardian,
+        cancellation_cutoff: config.cancellation_cutoff,
+        updated_at: clock.unix_timestamp,
+    });
+    
+    msg!("Program config updated");
+    msg!("Platform treasury: {}", config.platform_treasury);
+    msg!("Platform fee: {}%", config.platform_fee_rate as f64 / 100.0);
+    msg!("Paused: {}", config.paused);
+    
+    Ok(())
+}
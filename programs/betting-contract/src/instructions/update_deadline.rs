@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+
+#[derive(Accounts)]
+pub struct UpdateDeadline<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config capping how far out a deadline can move
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub signer: Signer<'info>, // Admin or a moderator
+}
+
+pub fn handler(ctx: Context<UpdateDeadline>, betting_deadline: i64) -> Result<()> {
+    let signer_key = ctx.accounts.signer.key();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    if signer_key != betting_pool.admin && !betting_pool.is_moderator(&signer_key) {
+        return Err(BettingError::UnauthorizedAdmin.into());
+    }
+    if betting_pool.is_cancelled() {
+        return Err(BettingError::PoolCancelled.into());
+    }
+    // Once the deadline has passed betting is over, even if nobody locked the pool yet
+    if !betting_pool.is_betting_open() {
+        return Err(BettingError::BettingClosed.into());
+    }
+
+    // The deadline can come in as far as now, closing betting at once, but never into the past
+    let clock = Clock::get()?;
+    require!(betting_deadline >= clock.unix_timestamp, BettingError::InvalidDeadline);
+    // Measured from the deadline the pool was created with, so repeated extensions can't add up
+    let latest_deadline = betting_pool.original_deadline
+        .checked_add(ctx.accounts.config.max_deadline_extension)
+        .ok_or(BettingError::ArithmeticOverflow)?;
+    require!(betting_deadline <= latest_deadline, BettingError::DeadlineTooFar);
+
+    let previous_deadline = betting_pool.betting_deadline;
+    betting_pool.betting_deadline = betting_deadline;
+
+    emit!(DeadlineChanged {
+        betting_pool: betting_pool.key(),
+        stream_id: betting_pool.stream_id.clone(),
+        previous_deadline,
+        betting_deadline,
+        changed_by: signer_key,
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Betting deadline changed for stream: {}", betting_pool.stream_id);
+    msg!("Deadline: {} -> {}", previous_deadline, betting_deadline);
+
+    Ok(())
+}
+
+// Event emitted when a pool's betting deadline moves
+#[event]
+pub struct DeadlineChanged {
+    pub betting_pool: Pubkey,
+    pub stream_id: String,
+    pub previous_deadline: i64,
+    pub betting_deadline: i64,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
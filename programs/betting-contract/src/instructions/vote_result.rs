@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::BettingError;
+use super::declare_winner::{declare_result, require_resolvable};
+
+#[derive(Accounts)]
+pub struct VoteResult<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Votes cast so far, created by the first voter
+    #[account(
+        init_if_needed,
+        payer = moderator,
+        space = ResolutionVotes::LEN,
+        seeds = [b"resolution_votes", betting_pool.key().as_ref()],
+        bump
+    )]
+    pub resolution_votes: Account<'info, ResolutionVotes>,
+
+    /// Member of the pool's moderator set
+    #[account(mut)]
+    pub moderator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<VoteResult>, winning_outcome: u8) -> Result<()> {
+    ctx.accounts.betting_pool.require_not_paused(&ctx.accounts.config)?;
+    let moderator = ctx.accounts.moderator.key();
+    let betting_pool = &mut ctx.accounts.betting_pool;
+
+    require!(betting_pool.uses_moderator_votes(), BettingError::InvalidModeratorSet);
+    require!(betting_pool.moderators.contains(&moderator), BettingError::UnauthorizedModerator);
+    require_resolvable(betting_pool, winning_outcome)?;
+
+    let resolution_votes = &mut ctx.accounts.resolution_votes;
+    require!(!resolution_votes.has_voted(&moderator), BettingError::AlreadyVoted);
+
+    let clock = Clock::get()?;
+    let betting_pool_key = betting_pool.key();
+    resolution_votes.betting_pool = betting_pool_key;
+    resolution_votes.bump = ctx.bumps.resolution_votes;
+    resolution_votes.votes.push(ModeratorVote {
+        moderator,
+        outcome: winning_outcome,
+        voted_at: clock.unix_timestamp,
+    });
+
+    let agreeing_votes = resolution_votes.votes_for(winning_outcome);
+    let is_conflicting = resolution_votes.is_conflicting();
+
+    emit!(ResultVoted {
+        betting_pool: betting_pool_key,
+        moderator,
+        winning_outcome,
+        agreeing_votes,
+        threshold: betting_pool.moderator_threshold,
+        is_conflicting,
+        voted_at: clock.unix_timestamp,
+    });
+
+    msg!("Result vote for stream: {}", betting_pool.stream_id);
+    msg!("Outcome {} has {} of {} votes needed", winning_outcome, agreeing_votes, betting_pool.moderator_threshold);
+
+    // Moderators disagreeing means the result is contested, the arbiter settles it with resolve_dispute
+    if is_conflicting {
+        msg!("Moderators disagree, result disputed");
+        return betting_pool.set_status(betting_pool_key, PoolStatus::Disputed);
+    }
+    if agreeing_votes >= betting_pool.moderator_threshold {
+        declare_result(betting_pool, winning_outcome)?;
+    }
+
+    Ok(())
+}
+
+// Event emitted for every moderator vote
+#[event]
+pub struct ResultVoted {
+    pub betting_pool: Pubkey,
+    pub moderator: Pubkey,
+    pub winning_outcome: u8,
+    pub agreeing_votes: u8,
+    pub threshold: u8,
+    pub is_conflicting: bool,
+    pub voted_at: i64,
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::error::BettingError;
+use crate::transfers::{self, TokenTransferAccounts};
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &betting_pool.match_number.to_le_bytes(),
+        ],
+        bump = betting_pool.bump,
+        has_one = admin @ BettingError::UnauthorizedAdmin,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    /// Program config holding the global pause flag
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// The admin receiving the liquidity back
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// System-owned vault holding the pool's SOL stakes
+    #[account(
+        mut,
+        seeds = [b"vault", betting_pool.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Mint of a token pool. Leave out for native SOL pools
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The token pool's vault
+    #[account(
+        mut,
+        seeds = [b"token_vault", betting_pool.key().as_ref()],
+        bump,
+    )]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin's token account receiving the liquidity
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program,
+    )]
+    pub admin_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawLiquidity>) -> Result<()> {
+    let betting_pool = &mut ctx.accounts.betting_pool;
+    require!(!betting_pool.is_parimutuel(), BettingError::InvalidMarketType);
+
+    // Cancelled pools hand the liquidity straight back, like their refunds
+    if !betting_pool.is_cancelled() {
+        betting_pool.require_not_paused(&ctx.accounts.config)?;
+        betting_pool.require_settleable()?;
+    }
+
+    // The liquidity plus every stake not owed to bettors or the platform
+    let amount = betting_pool.returnable_liquidity();
+    require!(amount > 0, BettingError::NothingToPayout);
+    require!(betting_pool.liquidity_returned == 0, BettingError::LiquidityAlreadyReturned);
+
+    let token = TokenTransferAccounts::for_pool(
+        betting_pool,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    transfers::pay_from_pool(
+        betting_pool,
+        &ctx.accounts.vault,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        token,
+        amount,
+    )?;
+
+    betting_pool.record_liquidity_return(amount)?;
+    let betting_pool_key = betting_pool.key();
+    betting_pool.advance_settlement(betting_pool_key)?;
+
+    let clock = Clock::get()?;
+
+    emit!(LiquidityWithdrawn {
+        betting_pool: betting_pool_key,
+        admin: ctx.accounts.admin.key(),
+        amount,
+        liquidity: betting_pool.liquidity,
+        withdrawn_at: clock.unix_timestamp,
+    });
+
+    msg!("Liquidity returned for stream: {}", betting_pool.stream_id);
+    msg!("Amount: {} lamports (deposited {} lamports)", amount, betting_pool.liquidity);
+
+    Ok(())
+}
+
+// Event emitted when a fixed-odds pool's admin takes back its liquidity and winnings
+#[event]
+pub struct LiquidityWithdrawn {
+    pub betting_pool: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub liquidity: u64,
+    pub withdrawn_at: i64,
+}
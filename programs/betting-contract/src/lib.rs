@@ -4,41 +4,199 @@ pub mod constants;
 pub mod instructions;
 pub mod state;
 pub mod error;
+pub mod transfers;
+pub mod attestation;
+pub mod pricing;
 
+pub use instructions::*;
+pub use state::{ConfigParams, MarketType, RefundFeePolicy, ResolverConfig, ResolverKind};
 
 declare_id!("DRNEUsSx9gNre6f6mLFhrHDVRDfD4eMGu68dussziUgi");
 
-#[program]
-pub mod betting_contract {
+// `#[program]` generates Anchor's IDL handlers next to the module, and they still call the
+// deprecated `AccountInfo::realloc`, so the module sits in its own scope that allows it.
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-     pub fn initialize(
-        ctx: Context<Initialize>,
-        stream_id: String,
-        betting_deadline: i64,
-        moderator_pubkey: Pubkey,
-        platform_treasury: Pubkey,
-    ) -> Result<()> {
-        instructions::initialize::handler(ctx, stream_id, betting_deadline, moderator_pubkey, platform_treasury)
-    }
+    #[program]
+    pub mod betting_contract {
+        use super::*;
 
-     pub fn place_bet(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()> {
-        instructions::place_bet::handler(ctx, prediction, amount)
-    }
+        #[allow(clippy::too_many_arguments)]
+        pub fn initialize(
+            ctx: Context<Initialize>,
+            stream_id: String,
+            match_number: u64,
+            betting_deadline: i64,
+            moderator_pubkey: Pubkey,
+            creator_fee_rate: Option<u16>,
+            refund_fee_policy: RefundFeePolicy,
+            outcome_labels: Vec<String>,
+            resolver: Option<ResolverConfig>,
+            market_type: MarketType,
+        ) -> Result<()> {
+            instructions::initialize::handler(ctx, stream_id, match_number, betting_deadline, moderator_pubkey, creator_fee_rate, refund_fee_policy, outcome_labels, resolver, market_type)
+        }
 
-    pub fn declare_winner(ctx: Context<DeclareWinner>, winning_outcome: u8) -> Result<()> {
-        instructions::declare_winner::handler(ctx, winning_outcome)
-    }
-    
-    pub fn payout_winners(ctx: Context<PayoutWinners>) -> Result<()> {
-        instructions::payout_winners::handler(ctx)
-    }
-    
-    pub fn payout_creator(ctx: Context<PayoutCreator>) -> Result<()> {
-        instructions::payout_creator::handler(ctx)
-    }
-    
-    pub fn payout_platform(ctx: Context<PayoutPlatform>) -> Result<()> {
-        instructions::payout_platform::handler(ctx)
+         pub fn place_bet(ctx: Context<PlaceBet>, prediction: u8, amount: u64) -> Result<()> {
+            instructions::place_bet::handler(ctx, prediction, amount)
+        }
+
+        pub fn declare_winner(ctx: Context<DeclareWinner>, winning_outcome: u8) -> Result<()> {
+            instructions::declare_winner::handler(ctx, winning_outcome)
+        }
+        
+        pub fn payout_winners(ctx: Context<PayoutWinners>) -> Result<()> {
+            instructions::payout_winners::handler(ctx)
+        }
+        
+        pub fn payout_creator(ctx: Context<PayoutCreator>) -> Result<()> {
+            instructions::payout_creator::handler(ctx)
+        }
+        
+        pub fn payout_platform(ctx: Context<PayoutPlatform>) -> Result<()> {
+            instructions::payout_platform::handler(ctx)
+        }
+
+        pub fn cancel_pool(ctx: Context<CancelPool>) -> Result<()> {
+            instructions::cancel_pool::handler(ctx)
+        }
+
+        pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+            instructions::claim_refund::handler(ctx)
+        }
+
+        pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+            instructions::initialize_config::handler(ctx, params)
+        }
+
+        pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+            instructions::update_config::handler(ctx, params)
+        }
+
+        pub fn close_losing_bet(ctx: Context<CloseLosingBet>) -> Result<()> {
+            instructions::close_losing_bet::handler(ctx)
+        }
+
+        pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+            instructions::close_pool::handler(ctx)
+        }
+
+        pub fn payout_winners_batch<'info>(
+            ctx: Context<'_, '_, 'info, 'info, PayoutWinnersBatch<'info>>,
+        ) -> Result<()> {
+            instructions::payout_winners_batch::handler(ctx)
+        }
+
+        pub fn lock_pool(ctx: Context<LockPool>) -> Result<()> {
+            instructions::lock_pool::handler(ctx)
+        }
+
+        pub fn dispute_result(ctx: Context<DisputeResult>, proposed_outcome: u8) -> Result<()> {
+            instructions::dispute_result::handler(ctx, proposed_outcome)
+        }
+
+        pub fn resolve_dispute(ctx: Context<ResolveDispute>, winning_outcome: u8) -> Result<()> {
+            instructions::resolve_dispute::handler(ctx, winning_outcome)
+        }
+
+        pub fn set_moderators(ctx: Context<SetModerators>, moderators: Vec<Pubkey>, threshold: u8) -> Result<()> {
+            instructions::set_moderators::handler(ctx, moderators, threshold)
+        }
+
+        pub fn vote_result(ctx: Context<VoteResult>, winning_outcome: u8) -> Result<()> {
+            instructions::vote_result::handler(ctx, winning_outcome)
+        }
+
+        pub fn set_attestor(ctx: Context<SetAttestor>, attestor: Option<Pubkey>) -> Result<()> {
+            instructions::set_attestor::handler(ctx, attestor)
+        }
+
+        pub fn declare_winner_with_attestation(
+            ctx: Context<DeclareWinnerWithAttestation>,
+            winning_outcome: u8,
+            attested_at: i64,
+        ) -> Result<()> {
+            instructions::declare_winner_with_attestation::handler(ctx, winning_outcome, attested_at)
+        }
+
+        pub fn resolve_from_oracle(ctx: Context<ResolveFromOracle>) -> Result<()> {
+            instructions::resolve_from_oracle::handler(ctx)
+        }
+
+        pub fn set_moderator(ctx: Context<SetModerator>, moderator: Pubkey) -> Result<()> {
+            instructions::set_moderator::handler(ctx, moderator)
+        }
+
+        pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+            instructions::propose_admin::handler(ctx, new_admin)
+        }
+
+        pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+            instructions::accept_admin::handler(ctx)
+        }
+
+        pub fn set_treasury(ctx: Context<SetTreasury>, platform_treasury: Pubkey) -> Result<()> {
+            instructions::set_treasury::handler(ctx, platform_treasury)
+        }
+
+        pub fn update_deadline(ctx: Context<UpdateDeadline>, betting_deadline: i64) -> Result<()> {
+            instructions::update_deadline::handler(ctx, betting_deadline)
+        }
+
+        pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+            instructions::set_paused::handler(ctx, paused)
+        }
+
+        pub fn set_pool_paused(ctx: Context<SetPoolPaused>, paused: bool) -> Result<()> {
+            instructions::set_pool_paused::handler(ctx, paused)
+        }
+
+        pub fn set_cancellation_fee(ctx: Context<SetCancellationFee>, cancellation_fee_rate: u16) -> Result<()> {
+            instructions::set_cancellation_fee::handler(ctx, cancellation_fee_rate)
+        }
+
+        pub fn cancel_bet(ctx: Context<CancelBet>) -> Result<()> {
+            instructions::cancel_bet::handler(ctx)
+        }
+
+        pub fn set_odds(ctx: Context<SetOdds>, odds: Vec<u32>) -> Result<()> {
+            instructions::set_odds::handler(ctx, odds)
+        }
+
+        pub fn deposit_liquidity(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
+            instructions::deposit_liquidity::handler(ctx, amount)
+        }
+
+        pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
+            instructions::withdraw_liquidity::handler(ctx)
+        }
+
+        pub fn seed_market(ctx: Context<SeedMarket>, liquidity_parameter: u64) -> Result<()> {
+            instructions::seed_market::handler(ctx, liquidity_parameter)
+        }
+
+        pub fn buy_shares(ctx: Context<BuyShares>, outcome: u8, shares: u64, max_cost: u64) -> Result<()> {
+            instructions::buy_shares::handler(ctx, outcome, shares, max_cost)
+        }
+
+        pub fn sell_shares(ctx: Context<SellShares>, shares: u64, min_proceeds: u64) -> Result<()> {
+            instructions::sell_shares::handler(ctx, shares, min_proceeds)
+        }
+
+        pub fn declare_legacy_result(ctx: Context<DeclareLegacyResult>, winning_outcome: u8) -> Result<()> {
+            instructions::declare_legacy_result::handler(ctx, winning_outcome)
+        }
+
+        pub fn settle_legacy_bet(ctx: Context<SettleLegacyBet>) -> Result<()> {
+            instructions::settle_legacy_bet::handler(ctx)
+        }
+
+        pub fn close_legacy_pool(ctx: Context<CloseLegacyPool>) -> Result<()> {
+            instructions::close_legacy_pool::handler(ctx)
+        }
     }
 }
+
+pub use program_module::*;
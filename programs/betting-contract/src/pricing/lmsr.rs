@@ -0,0 +1,424 @@
+//! Logarithmic market scoring rule (LMSR) market maker.
+//!
+//! With `q_i` shares outstanding on each outcome and a liquidity parameter `b`, the market's
+//! cost function is `C(q) = b * ln(sum(exp(q_i / b)))`. A trade costs the change in `C`, and
+//! outcome `i` is priced at `exp(q_i / b) / sum(exp(q_j / b))`, so prices always sum to one.
+//! Whatever happens, the market maker loses at most `C(0) = b * ln(n)`.
+//!
+//! Shares and amounts are in the pool's base units. Internally everything is fixed point in
+//! i128 with `PRICE_SCALE` as one. Amounts the pool receives round up and amounts it pays round
+//! down, so the pool never ends up owing more than it holds.
+
+/// Fixed-point one: prices are scaled by this
+pub const PRICE_SCALE: u64 = 1_000_000_000_000;
+
+const ONE: i128 = PRICE_SCALE as i128;
+
+/// Extra precision the series are computed at before rounding back to `PRICE_SCALE`
+const GUARD: i128 = 1_000_000;
+
+/// ln(2) scaled by `PRICE_SCALE * GUARD`
+const LN_2: i128 = 693_147_180_559_945_309;
+
+/// Exponents from here on are below the scale's resolution, e^-40 < 1e-17
+const MAX_EXPONENT: i128 = 40 * ONE;
+
+/// Series terms, enough to converge at this precision for the reduced arguments below
+const SERIES_TERMS: i128 = 30;
+
+/// Round a value at `PRICE_SCALE * GUARD` back to `PRICE_SCALE`
+fn round_guard(value: i128) -> i128 {
+    (value + GUARD / 2) / GUARD
+}
+
+/// `e^-x` for `x >= 0`, both scaled by `PRICE_SCALE`
+fn exp_neg(x: i128) -> i128 {
+    if x >= MAX_EXPONENT {
+        return 0;
+    }
+    let precise_one = ONE * GUARD;
+    let x = x * GUARD;
+    // e^-x = 2^-k * e^-r with 0 <= r < ln 2, where the Taylor series converges quickly
+    let k = x / LN_2;
+    let r = x - k * LN_2;
+    let mut term = precise_one;
+    let mut sum = precise_one;
+    for n in 1..SERIES_TERMS {
+        term = -term * r / (n * precise_one);
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+    round_guard(sum >> k)
+}
+
+/// `ln(y)` for `y >= 1`, both scaled by `PRICE_SCALE`
+fn ln(y: i128) -> i128 {
+    let precise_one = ONE * GUARD;
+    // ln y = k * ln 2 + ln(y / 2^k) with 1 <= y / 2^k < 2
+    let mut k = 0;
+    while y >= ONE << (k + 1) {
+        k += 1;
+    }
+    let base = ONE << k;
+    // ln m = 2 * atanh(z) = 2 * (z + z^3/3 + z^5/5 + ...) with z = (m - 1) / (m + 1) <= 1/3
+    let z = (y - base) * precise_one / (y + base);
+    let z_squared = z * z / precise_one;
+    let mut power = z;
+    let mut sum = 0;
+    for n in 0..SERIES_TERMS {
+        if power == 0 {
+            break;
+        }
+        sum += power / (2 * n + 1);
+        power = power * z_squared / precise_one;
+    }
+    round_guard(k as i128 * LN_2 + 2 * sum)
+}
+
+/// `exp(q_i / b)` for every outcome, scaled down by the largest so they lie between 0 and one.
+/// The largest comes out exactly one, so their sum is between one and `n`
+fn scaled_exponentials(shares: &[u64], liquidity_parameter: u64) -> Option<Vec<i128>> {
+    if liquidity_parameter == 0 || shares.is_empty() {
+        return None;
+    }
+    let b = liquidity_parameter as i128;
+    let max = *shares.iter().max()? as i128;
+    Some(
+        shares
+            .iter()
+            .map(|quantity| exp_neg((max - *quantity as i128) * ONE / b))
+            .collect(),
+    )
+}
+
+/// The cost function `C(q)` in base units, scaled by `PRICE_SCALE`.
+/// Returns `None` without outcomes or with a zero liquidity parameter.
+pub fn cost(shares: &[u64], liquidity_parameter: u64) -> Option<i128> {
+    let exponentials = scaled_exponentials(shares, liquidity_parameter)?;
+    let max = *shares.iter().max()? as i128;
+    let sum: i128 = exponentials.iter().sum();
+    Some(max * ONE + liquidity_parameter as i128 * ln(sum))
+}
+
+/// `numerator / ONE` rounded up, for `numerator >= 0`
+fn div_ceil(numerator: i128) -> i128 {
+    (numerator + ONE - 1) / ONE
+}
+
+/// What buying `amount` shares of outcome `index` (0-based) costs, rounded up to at least one
+/// base unit. Returns `None` for an unknown outcome or a zero amount.
+pub fn buy_cost(shares: &[u64], index: usize, amount: u64, liquidity_parameter: u64) -> Option<u64> {
+    if amount == 0 {
+        return None;
+    }
+    let mut after = shares.to_vec();
+    let quantity = after.get_mut(index)?;
+    *quantity = quantity.checked_add(amount)?;
+    let change = cost(&after, liquidity_parameter)? - cost(shares, liquidity_parameter)?;
+    u64::try_from(div_ceil(change.max(0)).max(1)).ok()
+}
+
+/// What selling `amount` shares of outcome `index` (0-based) back to the market pays, rounded down.
+/// Returns `None` for an unknown outcome, a zero amount, or more shares than are outstanding.
+pub fn sell_proceeds(shares: &[u64], index: usize, amount: u64, liquidity_parameter: u64) -> Option<u64> {
+    if amount == 0 {
+        return None;
+    }
+    let mut after = shares.to_vec();
+    let quantity = after.get_mut(index)?;
+    *quantity = quantity.checked_sub(amount)?;
+    let change = cost(shares, liquidity_parameter)? - cost(&after, liquidity_parameter)?;
+    u64::try_from(change.max(0) / ONE).ok()
+}
+
+/// Every outcome's price scaled by `PRICE_SCALE`, rounded down
+pub fn prices(shares: &[u64], liquidity_parameter: u64) -> Option<Vec<u64>> {
+    let exponentials = scaled_exponentials(shares, liquidity_parameter)?;
+    let sum: i128 = exponentials.iter().sum();
+    exponentials
+        .iter()
+        .map(|exponential| u64::try_from(exponential * ONE / sum).ok())
+        .collect()
+}
+
+/// What `amount` shares of outcome `index` (0-based) are worth at the current prices, rounded down
+pub fn shares_value(shares: &[u64], index: usize, amount: u64, liquidity_parameter: u64) -> Option<u64> {
+    let price = *prices(shares, liquidity_parameter)?.get(index)?;
+    u64::try_from(amount as u128 * price as u128 / PRICE_SCALE as u128).ok()
+}
+
+/// The most the market maker can lose over `outcome_count` outcomes, `b * ln(n)` rounded up.
+/// Seeding the market with this much keeps every payout covered.
+pub fn max_loss(outcome_count: usize, liquidity_parameter: u64) -> Option<u64> {
+    let initial_cost = cost(&vec![0; outcome_count], liquidity_parameter)?;
+    u64::try_from(div_ceil(initial_cost)).ok()
+}
+
+/// The least traders have paid in net to bring the market from no shares to `shares`, rounded down.
+/// Trades round in the pool's favour, so the pool holds at least this much from them.
+pub fn collected(shares: &[u64], liquidity_parameter: u64) -> Option<u64> {
+    let change = cost(shares, liquidity_parameter)? - cost(&vec![0; shares.len()], liquidity_parameter)?;
+    u64::try_from(change.max(0) / ONE).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    /// Absolute difference between two scaled values
+    fn diff(a: i128, b: i128) -> i128 {
+        (a - b).abs()
+    }
+
+    /// Deterministic pseudo-random sequence for the trade simulations
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: u64) -> u64 {
+            self.0 = self.0.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            (self.0 >> 33) % bound
+        }
+    }
+
+    #[test]
+    fn exp_neg_matches_known_values() {
+        assert_eq!(exp_neg(0), ONE);
+        assert!(diff(exp_neg(round_guard(LN_2)), ONE / 2) <= 2);
+        // e^-1 = 0.367879441171442...
+        assert!(diff(exp_neg(ONE), 367_879_441_171) <= 2);
+        // e^-5 = 0.006737946999085...
+        assert!(diff(exp_neg(5 * ONE), 6_737_946_999) <= 2);
+        // e^-0.1 = 0.904837418035959...
+        assert!(diff(exp_neg(ONE / 10), 904_837_418_036) <= 2);
+    }
+
+    #[test]
+    fn exp_neg_vanishes_for_large_exponents() {
+        assert_eq!(exp_neg(MAX_EXPONENT), 0);
+        assert_eq!(exp_neg(i128::MAX / 2), 0);
+        // e^-39 = 1.15e-17, below the scale
+        assert_eq!(exp_neg(39 * ONE), 0);
+    }
+
+    #[test]
+    fn exp_neg_is_decreasing() {
+        let mut previous = exp_neg(0);
+        for step in 1..400 {
+            let value = exp_neg(step * ONE / 10);
+            assert!(value <= previous);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn ln_matches_known_values() {
+        assert_eq!(ln(ONE), 0);
+        assert!(diff(ln(2 * ONE), 693_147_180_560) <= 1);
+        // ln(e) = 1
+        assert!(diff(ln(2_718_281_828_459), ONE) <= 2);
+        // ln(16) = 2.772588722239781...
+        assert!(diff(ln(16 * ONE), 2_772_588_722_240) <= 4);
+        // ln(1.5) = 0.405465108108164...
+        assert!(diff(ln(3 * ONE / 2), 405_465_108_108) <= 2);
+        // ln(3) = 1.098612288668109...
+        assert!(diff(ln(3 * ONE), 1_098_612_288_668) <= 2);
+    }
+
+    #[test]
+    fn ln_inverts_exp_neg() {
+        for step in 0..30 {
+            let x = step * ONE / 7;
+            // ln(1 / e^-x) = x, computed as ln(ONE^2 / e^-x)
+            let y = ONE * ONE / exp_neg(x);
+            assert!(diff(ln(y), x) <= 1_000, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn initial_cost_is_b_ln_n() {
+        assert_eq!(max_loss(2, 1_000), Some(694));
+        assert_eq!(max_loss(2, SOL), Some(693_147_181));
+        // 16 * ln(16) = 44.36...
+        assert_eq!(max_loss(16, 16), Some(45));
+        assert!(diff(cost(&[0, 0, 0], SOL).unwrap(), SOL as i128 * 1_098_612_288_668) <= 4 * SOL as i128);
+    }
+
+    #[test]
+    fn equal_shares_have_equal_prices() {
+        assert_eq!(prices(&[0, 0], SOL), Some(vec![ONE as u64 / 2; 2]));
+        let prices = prices(&[5 * SOL; 4], SOL).unwrap();
+        assert!(prices.iter().all(|price| *price == PRICE_SCALE / 4));
+    }
+
+    #[test]
+    fn prices_sum_to_one() {
+        let cases: [&[u64]; 4] = [
+            &[0, 3 * SOL],
+            &[SOL, 2 * SOL, 7 * SOL],
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            &[100 * SOL, 0],
+        ];
+        for shares in cases {
+            let sum: u64 = prices(shares, SOL).unwrap().iter().sum();
+            // Each price rounds down by less than one unit
+            assert!(sum <= PRICE_SCALE && sum > PRICE_SCALE - shares.len() as u64, "{sum}");
+        }
+    }
+
+    #[test]
+    fn prices_match_the_softmax() {
+        // q = (1 SOL, 0) with b = 1 SOL: p_1 = e / (e + 1) = 0.731058578630005...
+        let prices = prices(&[SOL, 0], SOL).unwrap();
+        assert!(diff(prices[0] as i128, 731_058_578_630) <= 2);
+        assert!(diff(prices[1] as i128, 268_941_421_370) <= 2);
+    }
+
+    #[test]
+    fn buy_cost_matches_the_cost_function() {
+        // C(1, 0) - C(0, 0) = ln(1 + e) - ln 2 = 0.620114506958...
+        assert_eq!(buy_cost(&[0, 0], 0, SOL, SOL), Some(620_114_507));
+        assert_eq!(sell_proceeds(&[SOL, 0], 0, SOL, SOL), Some(620_114_506));
+    }
+
+    #[test]
+    fn buying_moves_prices() {
+        let before = prices(&[0, 0, 0], SOL).unwrap();
+        let after = prices(&[SOL / 2, 0, 0], SOL).unwrap();
+        assert!(after[0] > before[0]);
+        assert!(after[1] < before[1]);
+        assert!(after[2] < before[2]);
+    }
+
+    #[test]
+    fn cost_lies_between_the_start_and_end_prices() {
+        let shares = [2 * SOL, SOL, 0];
+        let amount = SOL / 3;
+        let cost = buy_cost(&shares, 1, amount, SOL).unwrap();
+        let price_before = prices(&shares, SOL).unwrap()[1];
+        let price_after = prices(&[2 * SOL, SOL + amount, 0], SOL).unwrap()[1];
+        let at_start = amount as u128 * price_before as u128 / PRICE_SCALE as u128;
+        let at_end = amount as u128 * price_after as u128 / PRICE_SCALE as u128;
+        assert!(cost as u128 > at_start && (cost as u128) <= at_end + 1);
+        // A share never costs more than the unit it can redeem for
+        assert!(cost < amount);
+    }
+
+    #[test]
+    fn buying_is_path_independent() {
+        let in_one = buy_cost(&[0, 0], 0, 3 * SOL, SOL).unwrap();
+        let first = buy_cost(&[0, 0], 0, SOL, SOL).unwrap();
+        let second = buy_cost(&[SOL, 0], 0, 2 * SOL, SOL).unwrap();
+        // Each trade rounds up by less than one unit
+        assert!(first + second >= in_one && first + second <= in_one + 1);
+    }
+
+    #[test]
+    fn round_trips_never_profit() {
+        let mut rng = Lcg(7);
+        for _ in 0..200 {
+            let shares = [rng.next(10 * SOL), rng.next(10 * SOL), rng.next(10 * SOL)];
+            let index = rng.next(3) as usize;
+            let amount = rng.next(5 * SOL) + 1;
+            let cost = buy_cost(&shares, index, amount, SOL).unwrap();
+            let mut after = shares;
+            after[index] += amount;
+            let proceeds = sell_proceeds(&after, index, amount, SOL).unwrap();
+            assert!(proceeds <= cost, "{proceeds} > {cost}");
+            assert!(cost - proceeds <= 1);
+        }
+    }
+
+    #[test]
+    fn tiny_buys_still_cost_something() {
+        // One share of a long shot is worth far less than a base unit
+        assert_eq!(buy_cost(&[30 * SOL, 0], 1, 1, SOL), Some(1));
+        assert_eq!(sell_proceeds(&[30 * SOL, 1], 1, 1, SOL), Some(0));
+    }
+
+    #[test]
+    fn rejects_invalid_trades() {
+        assert_eq!(buy_cost(&[0, 0], 2, SOL, SOL), None);
+        assert_eq!(buy_cost(&[0, 0], 0, 0, SOL), None);
+        assert_eq!(buy_cost(&[0, 0], 0, SOL, 0), None);
+        assert_eq!(buy_cost(&[u64::MAX, 0], 0, 1, SOL), None);
+        assert_eq!(sell_proceeds(&[SOL, 0], 1, 1, SOL), None);
+        assert_eq!(sell_proceeds(&[SOL, 0], 0, SOL + 1, SOL), None);
+        assert_eq!(sell_proceeds(&[SOL, 0], 0, 0, SOL), None);
+        assert_eq!(prices(&[], SOL), None);
+        assert_eq!(prices(&[0, 0], 0), None);
+        assert_eq!(shares_value(&[0, 0], 2, SOL, SOL), None);
+        assert_eq!(max_loss(0, SOL), None);
+    }
+
+    #[test]
+    fn handles_extreme_quantities() {
+        // A long shot far below the leader has a price of zero, and the leader a price of one
+        let prices = prices(&[u64::MAX, 0], 1).unwrap();
+        assert_eq!(prices, vec![PRICE_SCALE, 0]);
+        assert_eq!(collected(&[u64::MAX / 2, 0], u64::MAX / 2).map(|paid| paid > 0), Some(true));
+        assert!(cost(&[u64::MAX; 16], u64::MAX).is_some());
+    }
+
+    #[test]
+    fn shares_are_valued_at_the_current_price() {
+        assert_eq!(shares_value(&[0, 0], 0, SOL, SOL), Some(SOL / 2));
+        assert_eq!(shares_value(&[SOL, 0], 1, 0, SOL), Some(0));
+    }
+
+    #[test]
+    fn collected_telescopes_over_trades() {
+        let shares = [0, 0];
+        let first = buy_cost(&shares, 0, 2 * SOL, SOL).unwrap();
+        let second = buy_cost(&[2 * SOL, 0], 1, SOL, SOL).unwrap();
+        let sold = sell_proceeds(&[2 * SOL, SOL], 0, SOL, SOL).unwrap();
+        let paid = first + second - sold;
+        let collected = collected(&[SOL, SOL], SOL).unwrap();
+        assert!(paid >= collected && paid <= collected + 3);
+    }
+
+    /// Simulate random buys and sells, tracking what the traders paid in net,
+    /// and check the seeded market can always pay out whatever happens
+    fn check_solvency(outcome_count: usize, liquidity_parameter: u64, seed: u64) {
+        let mut rng = Lcg(seed);
+        let mut shares = vec![0u64; outcome_count];
+        let seeded = max_loss(outcome_count, liquidity_parameter).unwrap();
+        let mut vault = seeded as u128;
+        for _ in 0..300 {
+            let index = rng.next(outcome_count as u64) as usize;
+            if rng.next(3) == 0 && shares[index] > 0 {
+                let amount = rng.next(shares[index]) + 1;
+                vault -= sell_proceeds(&shares, index, amount, liquidity_parameter).unwrap() as u128;
+                shares[index] -= amount;
+            } else {
+                let amount = rng.next(3 * liquidity_parameter) + 1;
+                vault += buy_cost(&shares, index, amount, liquidity_parameter).unwrap() as u128;
+                shares[index] += amount;
+            }
+
+            // The vault holds at least the seed plus what `collected` accounts for
+            let collected = collected(&shares, liquidity_parameter).unwrap() as u128;
+            assert!(vault >= seeded as u128 + collected, "{vault} < {seeded} + {collected}");
+            // Any outcome winning can be paid in full
+            let largest = *shares.iter().max().unwrap() as u128;
+            assert!(seeded as u128 + collected >= largest);
+            // Voiding at the current prices can be paid in full
+            let refunds: u128 = (0..outcome_count)
+                .map(|outcome| shares_value(&shares, outcome, shares[outcome], liquidity_parameter).unwrap() as u128)
+                .sum();
+            assert!(seeded as u128 + collected >= refunds, "{refunds}");
+        }
+    }
+
+    #[test]
+    fn seeded_market_stays_solvent() {
+        check_solvency(2, SOL, 1);
+        check_solvency(2, 1_000, 2);
+        check_solvency(3, 50 * SOL, 3);
+        check_solvency(5, 7, 4);
+        check_solvency(16, 10 * SOL, 5);
+    }
+}
@@ -0,0 +1,3 @@
+//! Pure pricing math, kept free of account types so it can be unit tested off-chain
+
+pub mod lmsr;
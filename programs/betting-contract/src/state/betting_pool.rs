@@ -1,64 +1,533 @@
 use anchor_lang::prelude::*;
+use crate::constants::{MAX_MODERATORS, MAX_OUTCOME_LABEL_LEN, ODDS_PRECISION, OUTCOME_DRAW, OUTCOME_VOID};
+use crate::error::BettingError;
+use crate::pricing::lmsr;
+use super::{Position, ProgramConfig, ResolverConfig};
+
+/// Whether creator and platform fees are taken when a pool settles as void or draw
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RefundFeePolicy {
+    /// Void and draw outcomes refund every bet in full
+    NoFees,
+    /// Draws pay creator and platform fees, voids refund in full
+    FeesOnDraw,
+    /// Void and draw outcomes both pay creator and platform fees
+    FeesOnVoidAndDraw,
+}
+
+/// How a pool prices its bets
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarketType {
+    /// Winners split the prize pool in proportion to their stakes
+    Parimutuel,
+    /// Each bet is paid at the odds it was placed at, backed by liquidity the creator deposits
+    FixedOdds,
+    /// Bettors trade outcome shares priced by a logarithmic market scoring rule,
+    /// and each winning share redeems for one base unit
+    Lmsr,
+}
+
+/// Lifecycle of a pool. Every instruction checks the status it starts from,
+/// and every change goes through `BettingPool::set_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolStatus {
+    /// Taking bets until the deadline
+    Open,
+    /// Betting closed, waiting for the result
+    Locked,
+    /// Result declared, nothing paid out yet
+    Resolved,
+    /// Result challenged, payouts blocked
+    Disputed,
+    /// Payouts and fees under way
+    Settling,
+    /// Every payout and fee paid
+    Settled,
+    /// Called off, every bet refunded in full
+    Cancelled,
+}
+
+impl PoolStatus {
+    /// Whether a pool in this status may move to `next`
+    pub fn can_transition_to(self, next: PoolStatus) -> bool {
+        use PoolStatus::*;
+        matches!(
+            (self, next),
+            (Open, Locked)
+                | (Open, Cancelled)
+                | (Locked, Resolved)
+                | (Locked, Cancelled)
+                | (Locked, Disputed)
+                | (Resolved, Disputed)
+                | (Resolved, Settling)
+                | (Resolved, Settled)
+                | (Disputed, Resolved)
+                | (Disputed, Cancelled)
+                | (Settling, Settled)
+        )
+    }
+}
+
+/// Emitted on every pool status change
+#[event]
+pub struct StatusChanged {
+    pub betting_pool: Pubkey,
+    pub previous_status: PoolStatus,
+    pub status: PoolStatus,
+    pub changed_at: i64,
+}
+
+/// One contestant (or result) users can bet on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Outcome {
+    /// Display label, e.g. the player's name
+    pub label: String,
+    /// Total lamports bet on this outcome
+    pub total_bets: u64,
+    /// Number of bets placed on this outcome
+    pub bet_count: u32,
+    /// Number of user positions holding this outcome
+    pub position_count: u32,
+    /// Decimal odds offered on this outcome in units of `ODDS_PRECISION`, 0 for parimutuel pools
+    pub odds: u32,
+    /// Owed to this outcome's bettors if it wins: fixed-odds payouts, or shares outstanding on an LMSR pool
+    pub liability: u64,
+}
+
+impl Outcome {
+    pub const LEN: usize =
+        4 + MAX_OUTCOME_LABEL_LEN + // label
+        8 + // total_bets
+        4 + // bet_count
+        4 + // position_count
+        4 + // odds
+        8; // liability
+}
 
 #[account]
 pub struct BettingPool {
     pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>, // Proposed by the admin, takes over once it accepts
     pub moderator: Pubkey, // NEW: Public key of the designated moderator
+    pub moderators: Vec<Pubkey>, // Moderator set voting on the result, empty for single-moderator pools
+    pub moderator_threshold: u8, // Agreeing votes needed to finalize a result, 0 without a moderator set
+    pub attestor: Option<Pubkey>, // Game server key whose signed results resolve the pool
+    pub resolver: Option<ResolverConfig>, // On-chain account the result can be read from
+    pub creator: Pubkey, // Admin that created the pool, part of the PDA seeds
     pub stream_id: String,
+    pub match_number: u64, // Lets a creator run several pools on one stream
     pub total_pool: u64,
-    pub player1_bets: u64,
-    pub player2_bets: u64,
-    pub player1_bet_count: u32,
-    pub player2_bet_count: u32,
-    pub winner_declared: bool,
+    pub market_type: MarketType,
+    pub liquidity: u64, // Deposited by the admin to back fixed odds or seed an LMSR market
+    pub liquidity_parameter: u64, // LMSR `b`, 0 until the market is seeded
+    pub outcomes: Vec<Outcome>, // Outcome N is stored at index N - 1
+    pub status: PoolStatus,
+    pub paused: bool, // Set by the guardian, blocks bets, results and payouts
     pub winning_outcome: u8,
     pub betting_deadline: i64,
+    pub original_deadline: i64, // Deadline the pool was created with, caps how far it can be pushed out
+    pub locked_at: i64, // When betting closed, 0 while open
+    pub resolution_delay: i64, // Seconds between lock and the earliest result
+    pub dispute_window: i64, // Seconds a declared result can be disputed
+    pub dispute_bond: u64, // Lamports a bettor posts to dispute the result
+    pub dispute_deadline: i64, // End of the dispute window, payouts open after it
+    pub cancellation_cutoff: i64, // Seconds before the deadline when bets stop being cancellable
+    pub cancellation_fee_rate: u16, // Kept from a cancelled bet, in basis points
     pub creator_fee_rate: u16,
     pub platform_fee_rate: u16,
     pub platform_treasury: Pubkey,
-    pub is_payout_complete: bool,
+    pub refund_fee_policy: RefundFeePolicy,
+    pub mint: Option<Pubkey>, // None for native SOL pools
+    // Settlement ledger, opened when the result is declared or the pool is cancelled
+    pub creator_fee_paid: u64,
+    pub platform_fee_paid: u64,
+    pub total_paid_to_winners: u64,
+    pub total_refunded: u64,
+    pub liquidity_returned: u64, // Paid back to the admin once a fixed-odds pool settles
+    pub fees_retained: u64, // Cancellation fees kept in the vault for the platform
+    pub fees_retained_paid: u64,
+    pub remaining_claimable: u64, // Still owed to bettors
+    pub remaining_claims: u32, // Positions still to be paid out or refunded
+    pub closed_positions: u32, // Positions closed after settling, the pool closes once all are
     pub created_at: i64,
     pub bump: u8,
+    pub vault_bump: u8,
 }
 
 impl BettingPool {
-   
+
+    /// Space without the outcome entries, see `BettingPool::space`
     pub const LEN: usize = 8 +
         32 + // admin
+        1 + 32 + // pending_admin
         32 + // moderator
+        4 + 32 * MAX_MODERATORS as usize + // moderators
+        1 + // moderator_threshold
+        1 + 32 + // attestor
+        1 + ResolverConfig::LEN + // resolver
+        32 + // creator
         4 + 32 + // stream_id (max 32 chars)
+        8 + // match_number
         8 + // total_pool
-        8 + // player1_bets
-        8 + // player2_bets
-        4 + // player1_bet_count
-        4 + // player2_bet_count
-        1 + // winner_declared
+        1 + // market_type
+        8 + // liquidity
+        8 + // liquidity_parameter
+        4 + // outcomes (vec length, entries added by space())
+        1 + // status
+        1 + // paused
         1 + // winning_outcome
         8 + // betting_deadline
+        8 + // original_deadline
+        8 + // locked_at
+        8 + // resolution_delay
+        8 + // dispute_window
+        8 + // dispute_bond
+        8 + // dispute_deadline
+        8 + // cancellation_cutoff
+        2 + // cancellation_fee_rate
         2 + // creator_fee_rate
         2 + // platform_fee_rate
         32 + // platform_treasury
-        1 + // is_payout_complete
+        1 + // refund_fee_policy
+        1 + 32 + // mint
+        8 + // creator_fee_paid
+        8 + // platform_fee_paid
+        8 + // total_paid_to_winners
+        8 + // total_refunded
+        8 + // liquidity_returned
+        8 + // fees_retained
+        8 + // fees_retained_paid
+        8 + // remaining_claimable
+        4 + // remaining_claims
+        4 + // closed_positions
         8 + // created_at
-        1; // bump
+        1 + // bump
+        1; // vault_bump
+
+    /// Account space for a pool with `outcome_count` outcomes
+    pub fn space(outcome_count: usize) -> usize {
+        Self::LEN + outcome_count * Outcome::LEN
+    }
 
     pub fn is_betting_open(&self) -> bool {
         let now = Clock::get().unwrap().unix_timestamp;
-        !self.winner_declared && now < self.betting_deadline
+        self.status == PoolStatus::Open && now < self.betting_deadline
+    }
+
+    /// Check neither the program nor this pool is paused
+    pub fn require_not_paused(&self, config: &ProgramConfig) -> Result<()> {
+        require!(!config.paused, BettingError::ProgramPaused);
+        require!(!self.paused, BettingError::PoolPaused);
+        Ok(())
+    }
+
+    /// Whether the result is decided by moderator votes rather than a single key
+    pub fn uses_moderator_votes(&self) -> bool {
+        self.moderator_threshold > 0
+    }
+
+    /// Whether `key` is the pool's moderator or belongs to its moderator set
+    pub fn is_moderator(&self, key: &Pubkey) -> bool {
+        *key == self.moderator || self.moderators.contains(key)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status == PoolStatus::Cancelled
+    }
+
+    /// Whether a result (winner, void or draw) has been declared
+    pub fn is_result_declared(&self) -> bool {
+        matches!(
+            self.status,
+            PoolStatus::Resolved | PoolStatus::Disputed | PoolStatus::Settling | PoolStatus::Settled
+        )
+    }
+
+    /// Check the pool's declared result can be paid out: not cancelled, declared,
+    /// not under dispute and past its dispute window
+    pub fn require_settleable(&self) -> Result<()> {
+        match self.status {
+            PoolStatus::Cancelled => err!(BettingError::PoolCancelled),
+            PoolStatus::Open | PoolStatus::Locked => err!(BettingError::WinnerNotDeclared),
+            PoolStatus::Disputed => err!(BettingError::InvalidPoolStatus),
+            PoolStatus::Resolved => {
+                require!(
+                    Clock::get()?.unix_timestamp >= self.dispute_deadline,
+                    BettingError::DisputeWindowOpen
+                );
+                Ok(())
+            }
+            PoolStatus::Settling | PoolStatus::Settled => Ok(()),
+        }
+    }
+
+    /// Move the pool to `status`, rejecting transitions its current status does not allow
+    pub fn set_status(&mut self, betting_pool: Pubkey, status: PoolStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(status),
+            BettingError::InvalidStatusTransition
+        );
+        let previous_status = self.status;
+        self.status = status;
+        emit!(StatusChanged {
+            betting_pool,
+            previous_status,
+            status,
+            changed_at: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Record `winning_outcome` as the pool's result and open the settlement ledger for it.
+    /// Returns the stake, bet count and position count that split the prize pool.
+    pub fn apply_result(&mut self, winning_outcome: u8) -> Result<(u64, u32, u32)> {
+        self.winning_outcome = winning_outcome;
+        // Void and draw refund every bet, so the whole pool counts as "winning"
+        let (winning_bets, winning_bet_count, winning_position_count) = if self.is_refund_outcome() {
+            (self.total_pool, self.total_bet_count(), self.total_position_count())
+        } else {
+            let outcome = self.outcome(winning_outcome).ok_or(BettingError::InvalidWinningOutcome)?;
+            (outcome.total_bets, outcome.bet_count, outcome.position_count)
+        };
+        // A side nobody backed cannot win a parimutuel pool; the pool has to be voided instead.
+        // A fixed-odds book or LMSR market simply keeps every stake
+        if !self.is_refund_outcome() && winning_bets == 0 && self.is_parimutuel() {
+            return Err(BettingError::NoBetsOnWinningOutcome.into());
+        }
+        // Winning positions split the prize pool, or are owed what their odds or shares promised.
+        // Void and draw refund every position
+        let claimable = if self.is_refund_outcome() {
+            self.refund_total()
+        } else if self.is_parimutuel() {
+            self.prize_pool()
+        } else {
+            self.outcome(winning_outcome).map_or(0, |outcome| outcome.liability)
+        };
+        self.open_settlement(winning_position_count, claimable);
+        Ok((winning_bets, winning_bet_count, winning_position_count))
+    }
+
+    /// Close betting at `locked_at`
+    pub fn lock(&mut self, betting_pool: Pubkey, locked_at: i64) -> Result<()> {
+        self.set_status(betting_pool, PoolStatus::Locked)?;
+        self.locked_at = locked_at;
+        Ok(())
+    }
+
+    /// Earliest time a result can be declared, once the pool is locked
+    pub fn resolution_opens_at(&self) -> Result<i64> {
+        self.locked_at
+            .checked_add(self.resolution_delay)
+            .ok_or(BettingError::ArithmeticOverflow.into())
+    }
+
+    /// Move a resolved pool to `Settling` after a payout or fee, and to `Settled` once everything is paid.
+    /// Cancelled pools keep their status while refunds go out.
+    pub fn advance_settlement(&mut self, betting_pool: Pubkey) -> Result<()> {
+        if !matches!(self.status, PoolStatus::Resolved | PoolStatus::Settling) {
+            return Ok(());
+        }
+        if self.is_payout_complete() {
+            self.set_status(betting_pool, PoolStatus::Settled)
+        } else if self.status == PoolStatus::Resolved {
+            self.set_status(betting_pool, PoolStatus::Settling)
+        } else {
+            Ok(())
+        }
     }
 
  
     pub fn total_bet_count(&self) -> u32 {
-        self.player1_bet_count + self.player2_bet_count
+        self.outcomes.iter().map(|outcome| outcome.bet_count).sum()
+    }
+
+    /// Number of user positions across every outcome
+    pub fn total_position_count(&self) -> u32 {
+        self.outcomes.iter().map(|outcome| outcome.position_count).sum()
+    }
+
+    /// Whether `outcome` (1-based) is one of this pool's outcomes
+    pub fn is_valid_outcome(&self, outcome: u8) -> bool {
+        outcome != OUTCOME_VOID && (outcome as usize) <= self.outcomes.len()
+    }
+
+    /// Look up an outcome by its 1-based number
+    pub fn outcome(&self, outcome: u8) -> Option<&Outcome> {
+        if !self.is_valid_outcome(outcome) {
+            return None;
+        }
+        self.outcomes.get(outcome as usize - 1)
+    }
+
+    /// Mutable lookup of an outcome by its 1-based number
+    pub fn outcome_mut(&mut self, outcome: u8) -> Option<&mut Outcome> {
+        if !self.is_valid_outcome(outcome) {
+            return None;
+        }
+        self.outcomes.get_mut(outcome as usize - 1)
+    }
+
+    /// Total lamports bet on `outcome`, zero for unknown outcomes
+    pub fn outcome_bets(&self, outcome: u8) -> u64 {
+        self.outcome(outcome).map_or(0, |outcome| outcome.total_bets)
+    }
+
+    /// Total lamports bet on the declared winning outcome
+    pub fn winning_bets(&self) -> u64 {
+        self.outcome_bets(self.winning_outcome)
+    }
+
+    /// Whether bets are made in an SPL token rather than native SOL
+    pub fn is_token_pool(&self) -> bool {
+        self.mint.is_some()
+    }
+
+    /// Whether winners split a shared prize pool
+    pub fn is_parimutuel(&self) -> bool {
+        self.market_type == MarketType::Parimutuel
+    }
+
+    /// Whether bets are paid at fixed odds rather than from a shared prize pool
+    pub fn is_fixed_odds(&self) -> bool {
+        self.market_type == MarketType::FixedOdds
+    }
+
+    /// Whether bettors trade LMSR-priced outcome shares
+    pub fn is_lmsr(&self) -> bool {
+        self.market_type == MarketType::Lmsr
+    }
+
+    /// Shares outstanding on every outcome of an LMSR pool, the state its prices come from
+    pub fn outcome_shares(&self) -> Vec<u64> {
+        self.outcomes.iter().map(|outcome| outcome.liability).collect()
+    }
+
+    /// What `shares` of `outcome` are worth at an LMSR pool's current prices, 0 before it is seeded
+    pub fn shares_value(&self, outcome: u8, shares: u64) -> u64 {
+        if !self.is_valid_outcome(outcome) {
+            return 0;
+        }
+        lmsr::shares_value(&self.outcome_shares(), outcome as usize - 1, shares, self.liquidity_parameter)
+            .unwrap_or(0)
+    }
+
+    /// What a stake of `amount` on `outcome` is paid if it wins, at the outcome's current odds
+    pub fn fixed_odds_payout(&self, outcome: u8, amount: u64) -> Result<u64> {
+        let odds = self.outcome(outcome).ok_or(BettingError::InvalidPrediction)?.odds;
+        require!(odds > ODDS_PRECISION, BettingError::InvalidOdds);
+        u64::try_from(amount as u128 * odds as u128 / ODDS_PRECISION as u128)
+            .map_err(|_| BettingError::ArithmeticOverflow.into())
+    }
+
+    /// Check the pool can pay every fixed-odds bettor whichever outcome wins: each outcome's
+    /// liability plus the platform fee must fit in the stakes and the admin's liquidity
+    pub fn require_liability_covered(&self) -> Result<()> {
+        let available = self.total_pool
+            .checked_add(self.liquidity)
+            .ok_or(BettingError::ArithmeticOverflow)?
+            .saturating_sub(self.calculate_platform_fee());
+        require!(
+            self.outcomes.iter().all(|outcome| outcome.liability <= available),
+            BettingError::LiquidityExceeded
+        );
+        Ok(())
+    }
+
+    /// Liquidity and unclaimed stakes owed back to the admin of a settled or cancelled
+    /// fixed-odds or LMSR pool
+    pub fn returnable_liquidity(&self) -> u64 {
+        if self.is_parimutuel() || !(self.is_result_declared() || self.is_cancelled()) {
+            return 0;
+        }
+        // An LMSR vault holds what the trades cost, which is not the bettors' cost basis
+        let received = if self.is_lmsr() {
+            lmsr::collected(&self.outcome_shares(), self.liquidity_parameter).unwrap_or(0)
+        } else {
+            self.total_pool
+        };
+        let owed_to_bettors = if self.is_cancelled() || self.is_refund_outcome() {
+            self.refund_total()
+        } else {
+            self.outcome(self.winning_outcome).map_or(0, |outcome| outcome.liability)
+        };
+        received
+            .saturating_add(self.liquidity)
+            .saturating_sub(owed_to_bettors)
+            .saturating_sub(self.calculate_platform_fee())
+    }
+
+    /// Whether the declared outcome refunds every bet instead of paying winners
+    pub fn is_refund_outcome(&self) -> bool {
+        self.is_result_declared()
+            && (self.winning_outcome == OUTCOME_VOID || self.winning_outcome == OUTCOME_DRAW)
     }
 
-    /// Calculate creator fee (2.5% of total pool)
+    /// Whether creator and platform fees are taken from this pool
+    pub fn charges_fees(&self) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        if !self.is_refund_outcome() {
+            return true;
+        }
+        match self.refund_fee_policy {
+            RefundFeePolicy::NoFees => false,
+            RefundFeePolicy::FeesOnDraw => self.winning_outcome == OUTCOME_DRAW,
+            RefundFeePolicy::FeesOnVoidAndDraw => true,
+        }
+    }
+
+    /// Take a whole position back out of the pool's totals when it leaves before the result
+    pub fn remove_position(&mut self, position: &Position) -> Result<()> {
+        self.total_pool = self.total_pool
+            .checked_sub(position.amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        let outcome = self
+            .outcome_mut(position.prediction)
+            .ok_or(BettingError::InvalidPrediction)?;
+        outcome.total_bets = outcome.total_bets
+            .checked_sub(position.amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        outcome.bet_count = outcome.bet_count
+            .checked_sub(position.bet_count)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        outcome.liability = outcome.liability
+            .checked_sub(position.potential_payout)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        outcome.position_count = outcome.position_count
+            .checked_sub(1)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether bets can still be cancelled: betting is open and the cutoff before the deadline has not started
+    pub fn is_cancellation_open(&self) -> Result<bool> {
+        let cutoff_starts_at = self.betting_deadline
+            .checked_sub(self.cancellation_cutoff)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(self.is_betting_open() && Clock::get()?.unix_timestamp < cutoff_starts_at)
+    }
+
+    /// Fee kept when a stake of `amount` is cancelled
+    pub fn calculate_cancellation_fee(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.cancellation_fee_rate as u128) / 10000) as u64
+    }
+
+    /// Calculate creator fee (2.5% of total pool).
+    /// Fixed-odds and LMSR pools take none: the admin keeps what the book or market wins instead
     pub fn calculate_creator_fee(&self) -> u64 {
+        if !self.charges_fees() || !self.is_parimutuel() {
+            return 0;
+        }
         (self.total_pool * self.creator_fee_rate as u64) / 10000
     }
 
-    /// Calculate platform fee (2.5% of total pool)
+    /// Calculate platform fee (2.5% of total pool).
+    /// LMSR pools take none, every share must stay redeemable for one unit
     pub fn calculate_platform_fee(&self) -> u64 {
+        if !self.charges_fees() || self.is_lmsr() {
+            return 0;
+        }
         (self.total_pool * self.platform_fee_rate as u64) / 10000
     }
 
@@ -66,4 +535,154 @@ impl BettingPool {
     pub fn prize_pool(&self) -> u64 {
         self.total_pool - self.calculate_creator_fee() - self.calculate_platform_fee()
     }
+
+    /// Calculate a winning bet's parimutuel share of the prize pool
+    pub fn calculate_payout(&self, bet_amount: u64) -> u64 {
+        let winning_bets = self.winning_bets();
+        if winning_bets == 0 {
+            return 0;
+        }
+        ((bet_amount as u128 * self.prize_pool() as u128) / winning_bets as u128) as u64
+    }
+
+    /// What a winning position is owed: its share of the prize pool, what its bets were promised
+    /// at the odds they were placed at, or one unit per LMSR share
+    pub fn winning_payout(&self, position: &Position) -> u64 {
+        if self.is_parimutuel() {
+            self.calculate_payout(position.amount)
+        } else {
+            position.potential_payout
+        }
+    }
+
+    /// Calculate the refund owed to a stake on a cancelled, void or drawn pool
+    pub fn refund_amount(&self, bet_amount: u64) -> u64 {
+        if self.is_cancelled() || self.total_pool == 0 {
+            return bet_amount;
+        }
+        ((bet_amount as u128 * self.prize_pool() as u128) / self.total_pool as u128) as u64
+    }
+
+    /// What a position is refunded by a cancelled, void or drawn pool.
+    /// LMSR shares are bought back at the market's final prices
+    pub fn position_refund(&self, position: &Position) -> u64 {
+        if self.is_lmsr() {
+            self.shares_value(position.prediction, position.potential_payout)
+        } else {
+            self.refund_amount(position.amount)
+        }
+    }
+
+    /// Everything a cancelled, void or drawn pool refunds between its positions
+    pub fn refund_total(&self) -> u64 {
+        if self.is_lmsr() {
+            let shares = self.outcome_shares();
+            return (0..shares.len())
+                .filter_map(|index| lmsr::shares_value(&shares, index, shares[index], self.liquidity_parameter))
+                .sum();
+        }
+        if self.is_cancelled() {
+            self.total_pool
+        } else {
+            self.prize_pool()
+        }
+    }
+
+    /// Open the settlement ledger once the pool's result is known.
+    /// `claims` positions are owed `claimable` between them.
+    pub fn open_settlement(&mut self, claims: u32, claimable: u64) {
+        self.remaining_claims = claims;
+        self.remaining_claimable = claimable;
+    }
+
+    /// Amount the next claim receives given its computed `share`.
+    /// The final claim takes whatever is left so rounding dust is not stranded.
+    pub fn next_claim_amount(&self, share: u64) -> u64 {
+        if self.remaining_claims == 1 {
+            self.remaining_claimable
+        } else {
+            share.min(self.remaining_claimable)
+        }
+    }
+
+    /// Record a winner payout or refund in the settlement ledger
+    pub fn record_claim(&mut self, amount: u64, is_refund: bool) -> Result<()> {
+        require!(self.remaining_claims > 0, BettingError::PayoutsAlreadyCompleted);
+        self.remaining_claims -= 1;
+        self.remaining_claimable = self.remaining_claimable
+            .checked_sub(amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        let total = if is_refund {
+            &mut self.total_refunded
+        } else {
+            &mut self.total_paid_to_winners
+        };
+        *total = total.checked_add(amount).ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record the creator fee payout, which can only happen once
+    pub fn record_creator_fee(&mut self, amount: u64) -> Result<()> {
+        require!(self.creator_fee_paid == 0, BettingError::FeeAlreadyPaid);
+        self.creator_fee_paid = amount;
+        Ok(())
+    }
+
+    /// Record the platform fee payout, which can only happen once
+    pub fn record_platform_fee(&mut self, amount: u64) -> Result<()> {
+        require!(self.platform_fee_paid == 0, BettingError::FeeAlreadyPaid);
+        self.platform_fee_paid = amount;
+        Ok(())
+    }
+
+    /// Record what a cancelled bet left behind in the vault for the platform
+    pub fn record_fee_retained(&mut self, amount: u64) -> Result<()> {
+        self.fees_retained = self.fees_retained
+            .checked_add(amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Retained fees not yet paid to the platform treasury
+    pub fn unpaid_fees_retained(&self) -> u64 {
+        self.fees_retained.saturating_sub(self.fees_retained_paid)
+    }
+
+    /// Record the retained fees paid to the platform treasury
+    pub fn record_fees_retained_paid(&mut self, amount: u64) -> Result<()> {
+        self.fees_retained_paid = self.fees_retained_paid
+            .checked_add(amount)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record the liquidity returned to the admin, which can only happen once
+    pub fn record_liquidity_return(&mut self, amount: u64) -> Result<()> {
+        require!(self.liquidity_returned == 0, BettingError::LiquidityAlreadyReturned);
+        self.liquidity_returned = amount;
+        Ok(())
+    }
+
+    /// Record that a settled position was closed and its rent returned
+    pub fn record_position_closed(&mut self) -> Result<()> {
+        self.closed_positions = self.closed_positions
+            .checked_add(1)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether every position on the pool has been closed
+    pub fn all_positions_closed(&self) -> bool {
+        self.closed_positions >= self.total_position_count()
+    }
+
+    /// Whether every claim and fee owed by a declared or cancelled pool has been paid
+    pub fn is_payout_complete(&self) -> bool {
+        (self.is_result_declared() || self.is_cancelled())
+            && self.remaining_claims == 0
+            && self.creator_fee_paid == self.calculate_creator_fee()
+            && self.platform_fee_paid == self.calculate_platform_fee()
+            && self.liquidity_returned == self.returnable_liquidity()
+            && self.fees_retained_paid == self.fees_retained
+    }
 }
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+/// A bonded challenge to a pool's declared result, seeded by `[b"dispute", betting_pool]`.
+/// The bond is held in this account until the arbiter rules.
+#[account]
+pub struct Dispute {
+    /// The betting pool whose result is disputed
+    pub betting_pool: Pubkey,
+    
+    /// Bettor who posted the bond
+    pub challenger: Pubkey,
+    
+    /// The declared result being challenged
+    pub disputed_outcome: u8,
+    
+    /// The result the challenger says is correct
+    pub proposed_outcome: u8,
+    
+    /// Lamports posted as bond
+    pub bond: u64,
+    
+    /// Unix timestamp when the dispute was raised
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const LEN: usize = 8 +
+        32 +  // betting_pool: Pubkey
+        32 +  // challenger: Pubkey
+        1 +   // disputed_outcome: u8
+        1 +   // proposed_outcome: u8
+        8 +   // bond: u64
+        8 +   // created_at: i64
+        1;    // bump: u8
+}
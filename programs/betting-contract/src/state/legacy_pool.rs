@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use crate::constants::{OUTCOME_DRAW, OUTCOME_VOID};
+use crate::error::BettingError;
+
+/// A pool created before pools were namespaced by creator, seeded by `[b"betting_pool", stream_id]`.
+/// It keeps the original two-player layout and holds its stakes in its own lamports.
+/// Legacy pools take no new bets; they are wound down with the `*_legacy_*` instructions.
+#[account(discriminator = [59, 136, 47, 53, 37, 99, 87, 104])] // Created as `BettingPool`
+pub struct LegacyBettingPool {
+    pub admin: Pubkey,
+    pub moderator: Pubkey,
+    pub stream_id: String,
+    pub total_pool: u64,
+    pub player1_bets: u64,
+    pub player2_bets: u64,
+    pub player1_bet_count: u32, // Bets on player 1 not settled yet
+    pub player2_bet_count: u32, // Bets on player 2 not settled yet
+    pub winner_declared: bool,
+    pub winning_outcome: u8,
+    pub betting_deadline: i64,
+    pub creator_fee_rate: u16,
+    pub platform_fee_rate: u16,
+    pub platform_treasury: Pubkey,
+    pub is_payout_complete: bool, // Every bet has been settled
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl LegacyBettingPool {
+    pub const LEN: usize = 8 +
+        32 + // admin
+        32 + // moderator
+        4 + 32 + // stream_id (max 32 chars)
+        8 + // total_pool
+        8 + // player1_bets
+        8 + // player2_bets
+        4 + // player1_bet_count
+        4 + // player2_bet_count
+        1 + // winner_declared
+        1 + // winning_outcome
+        8 + // betting_deadline
+        2 + // creator_fee_rate
+        2 + // platform_fee_rate
+        32 + // platform_treasury
+        1 + // is_payout_complete
+        8 + // created_at
+        1; // bump
+
+    /// Whether the declared result refunds every bet in full
+    pub fn is_refund_outcome(&self) -> bool {
+        self.winning_outcome == OUTCOME_VOID || self.winning_outcome == OUTCOME_DRAW
+    }
+
+    /// Total staked on player 1 or 2
+    pub fn outcome_bets(&self, outcome: u8) -> u64 {
+        match outcome {
+            1 => self.player1_bets,
+            2 => self.player2_bets,
+            _ => 0,
+        }
+    }
+
+    /// Creator fee on the whole pool, nothing when the result is a refund
+    pub fn creator_fee(&self) -> Result<u64> {
+        self.fee(self.creator_fee_rate)
+    }
+
+    /// Platform fee on the whole pool, nothing when the result is a refund
+    pub fn platform_fee(&self) -> Result<u64> {
+        self.fee(self.platform_fee_rate)
+    }
+
+    fn fee(&self, rate: u16) -> Result<u64> {
+        if self.is_refund_outcome() {
+            return Ok(0);
+        }
+        let fee = self.total_pool as u128 * rate as u128 / 10_000;
+        u64::try_from(fee).map_err(|_| BettingError::ArithmeticOverflow.into())
+    }
+
+    /// What `bet` is owed under the declared result: its stake on a refund, its share of the
+    /// prize pool if it won, nothing otherwise. Bets the previous program paid are owed nothing
+    pub fn settlement_amount(&self, bet: &LegacyBet) -> Result<u64> {
+        if bet.is_paid_out {
+            return Ok(0);
+        }
+        if self.is_refund_outcome() {
+            return Ok(bet.amount);
+        }
+        if bet.prediction != self.winning_outcome {
+            return Ok(0);
+        }
+        let fees = self.creator_fee()?
+            .checked_add(self.platform_fee()?)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        let prize_pool = self.total_pool
+            .checked_sub(fees)
+            .ok_or(BettingError::ArithmeticOverflow)?;
+        let winning_bets = self.outcome_bets(self.winning_outcome);
+        require!(winning_bets > 0, BettingError::NoBetsOnWinningOutcome);
+        let share = bet.amount as u128 * prize_pool as u128 / winning_bets as u128;
+        u64::try_from(share).map_err(|_| BettingError::ArithmeticOverflow.into())
+    }
+
+    /// Count `prediction`'s bet as settled, flagging the pool once none are left
+    pub fn record_bet_settled(&mut self, prediction: u8) -> Result<()> {
+        let count = match prediction {
+            1 => &mut self.player1_bet_count,
+            2 => &mut self.player2_bet_count,
+            _ => return Err(BettingError::InvalidPrediction.into()),
+        };
+        *count = count.checked_sub(1).ok_or(BettingError::ArithmeticOverflow)?;
+        self.is_payout_complete = self.player1_bet_count == 0 && self.player2_bet_count == 0;
+        Ok(())
+    }
+}
+
+/// A single bet on a legacy pool, seeded by `[b"bet", betting_pool, user, bet_index]`
+#[account(discriminator = [147, 23, 35, 59, 15, 75, 155, 32])] // Created as `Bet`
+pub struct LegacyBet {
+    pub user: Pubkey,
+    pub betting_pool: Pubkey,
+    pub amount: u64,
+    pub prediction: u8, // 1 = Player 1, 2 = Player 2
+    pub timestamp: i64,
+    pub is_paid_out: bool, // Paid by the previous program version
+    pub bet_index: u32,
+    pub bump: u8,
+}
+
+impl LegacyBet {
+    pub const LEN: usize = 8 +
+        32 + // user
+        32 + // betting_pool
+        8 + // amount
+        1 + // prediction
+        8 + // timestamp
+        1 + // is_paid_out
+        4 + // bet_index
+        1; // bump
+}
@@ -1,5 +1,15 @@
 pub mod betting_pool;
-pub mod bet;
+pub mod position;
+pub mod program_config;
+pub mod dispute;
+pub mod resolution_votes;
+pub mod resolver;
+pub mod legacy_pool;
 
 pub use betting_pool::*;
-pub use bet::*;
\ No newline at end of file
+pub use position::*;
+pub use program_config::*;
+pub use dispute::*;
+pub use resolution_votes::*;
+pub use resolver::*;
+pub use legacy_pool::*;
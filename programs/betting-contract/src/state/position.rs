@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// A user's aggregated stake on one outcome of a pool.
+/// Created by the user's first bet on that outcome and topped up by later bets.
+#[account]
+pub struct Position {
+    /// The user who holds this position
+    pub user: Pubkey,
+    
+    /// The betting pool this position belongs to
+    pub betting_pool: Pubkey,
+    
+    /// Total amount staked on the outcome (in lamports, or token base units)
+    pub amount: u64,
+    
+    /// User's prediction: the 1-based number of the outcome backed
+    pub prediction: u8,
+    
+    /// Paid if the outcome wins on a fixed-odds pool: each bet's stake times the odds it was placed at.
+    /// On an LMSR pool, the shares held, each paying one unit
+    pub potential_payout: u64,
+    
+    /// Number of bets placed into this position
+    pub bet_count: u32,
+    
+    /// Unix timestamp of the first bet
+    pub first_bet_at: i64,
+    
+    /// Unix timestamp of the latest bet
+    pub last_bet_at: i64,
+    
+    /// Whether this position has been paid out or refunded (prevents double payouts)
+    pub is_paid_out: bool,
+    
+    /// The order of the position's first bet in the pool (for display only)
+    pub bet_index: u32,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Position {
+    /// Calculate space needed for this account
+    /// 8 (discriminator) + account data
+    pub const LEN: usize = 8 +
+        32 +  // user: Pubkey
+        32 +  // betting_pool: Pubkey
+        8 +   // amount: u64
+        1 +   // prediction: u8
+        8 +   // potential_payout: u64
+        4 +   // bet_count: u32
+        8 +   // first_bet_at: i64
+        8 +   // last_bet_at: i64
+        1 +   // is_paid_out: bool
+        4 +   // bet_index: u32
+        1;    // bump: u8
+
+    /// Check if this position backs the winning outcome
+    pub fn is_winner(&self, winning_outcome: u8) -> bool {
+        self.prediction == winning_outcome && winning_outcome != 0
+    }
+
+    /// Validate that prediction is one of the pool's `outcome_count` outcomes
+    pub fn is_valid_prediction(&self, outcome_count: usize) -> bool {
+        self.prediction >= 1 && (self.prediction as usize) <= outcome_count
+    }
+
+    /// Whether this account was just created and has not taken a bet yet
+    pub fn is_new(&self) -> bool {
+        self.bet_count == 0
+    }
+}
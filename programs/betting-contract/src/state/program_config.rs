@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_FEE_RATE;
+use crate::error::BettingError;
+
+/// Settings an `update_config` call can change
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ConfigParams {
+    /// Platform authority allowed to update the config
+    pub authority: Pubkey,
+    /// Wallet receiving platform fees from new pools
+    pub platform_treasury: Pubkey,
+    /// Platform fee for new pools, in basis points
+    pub platform_fee_rate: u16,
+    /// Creator fee for new pools that don't pick their own, in basis points
+    pub default_creator_fee_rate: u16,
+    /// Highest creator fee a pool can pick, in basis points
+    pub max_creator_fee_rate: u16,
+    /// Smallest SOL bet accepted, in lamports
+    pub min_bet: u64,
+    /// Largest SOL bet accepted, in lamports
+    pub max_bet: u64,
+    /// Blocks new pools, bets, results and payouts while set. The guardian can also toggle it
+    pub paused: bool,
+    /// Seconds a new pool must stay locked before its result can be declared
+    pub min_resolution_delay: i64,
+    /// Seconds after a result is declared during which bettors can dispute it
+    pub dispute_window: i64,
+    /// Lamports a bettor posts to dispute a result
+    pub dispute_bond: u64,
+    /// Authority that rules on disputed results
+    pub arbiter: Pubkey,
+    /// Furthest past a pool's original deadline `update_deadline` can push it, in seconds
+    pub max_deadline_extension: i64,
+    /// Key that can pause the program or a single pool in an emergency
+    pub guardian: Pubkey,
+    /// Seconds before a new pool's betting deadline after which bets can no longer be cancelled
+    pub cancellation_cutoff: i64,
+}
+
+/// Program-wide settings, a singleton PDA seeded by `[b"config"]`
+#[account]
+pub struct ProgramConfig {
+    pub authority: Pubkey,
+    pub platform_treasury: Pubkey,
+    pub platform_fee_rate: u16,
+    pub default_creator_fee_rate: u16,
+    pub max_creator_fee_rate: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub paused: bool,
+    pub min_resolution_delay: i64,
+    pub dispute_window: i64,
+    pub dispute_bond: u64,
+    pub arbiter: Pubkey,
+    pub max_deadline_extension: i64,
+    pub guardian: Pubkey,
+    pub cancellation_cutoff: i64,
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 8 +
+        32 + // authority
+        32 + // platform_treasury
+        2 + // platform_fee_rate
+        2 + // default_creator_fee_rate
+        2 + // max_creator_fee_rate
+        8 + // min_bet
+        8 + // max_bet
+        1 + // paused
+        8 + // min_resolution_delay
+        8 + // dispute_window
+        8 + // dispute_bond
+        32 + // arbiter
+        8 + // max_deadline_extension
+        32 + // guardian
+        8 + // cancellation_cutoff
+        1; // bump
+
+    /// Validate and store `params`
+    pub fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        require!(
+            params.default_creator_fee_rate <= params.max_creator_fee_rate,
+            BettingError::InvalidFeeRate
+        );
+        require!(
+            params.max_creator_fee_rate as u32 + params.platform_fee_rate as u32 <= MAX_FEE_RATE as u32,
+            BettingError::InvalidFeeRate
+        );
+        require!(
+            params.min_bet > 0 && params.min_bet <= params.max_bet,
+            BettingError::InvalidBetLimits
+        );
+        require!(params.min_resolution_delay >= 0, BettingError::InvalidResolutionDelay);
+        require!(params.dispute_window >= 0, BettingError::InvalidDisputeWindow);
+        require!(params.max_deadline_extension >= 0, BettingError::InvalidDeadlineExtension);
+        require!(params.cancellation_cutoff >= 0, BettingError::InvalidCancellationCutoff);
+
+        self.authority = params.authority;
+        self.platform_treasury = params.platform_treasury;
+        self.platform_fee_rate = params.platform_fee_rate;
+        self.default_creator_fee_rate = params.default_creator_fee_rate;
+        self.max_creator_fee_rate = params.max_creator_fee_rate;
+        self.min_bet = params.min_bet;
+        self.max_bet = params.max_bet;
+        self.paused = params.paused;
+        self.min_resolution_delay = params.min_resolution_delay;
+        self.dispute_window = params.dispute_window;
+        self.dispute_bond = params.dispute_bond;
+        self.arbiter = params.arbiter;
+        self.max_deadline_extension = params.max_deadline_extension;
+        self.guardian = params.guardian;
+        self.cancellation_cutoff = params.cancellation_cutoff;
+        Ok(())
+    }
+
+    /// Creator fee for a new pool, falling back to the default when none is requested
+    pub fn creator_fee_rate(&self, requested: Option<u16>) -> Result<u16> {
+        let rate = requested.unwrap_or(self.default_creator_fee_rate);
+        require!(rate <= self.max_creator_fee_rate, BettingError::InvalidFeeRate);
+        Ok(rate)
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_MODERATORS;
+
+/// One moderator's vote on a pool's result
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ModeratorVote {
+    pub moderator: Pubkey,
+    pub outcome: u8,
+    pub voted_at: i64,
+}
+
+impl ModeratorVote {
+    pub const LEN: usize =
+        32 + // moderator
+        1 + // outcome
+        8; // voted_at
+}
+
+/// Votes cast by a pool's moderator set, seeded by `[b"resolution_votes", betting_pool]`
+#[account]
+pub struct ResolutionVotes {
+    /// The betting pool being voted on
+    pub betting_pool: Pubkey,
+    
+    /// Votes in the order they were cast
+    pub votes: Vec<ModeratorVote>,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl ResolutionVotes {
+    pub const LEN: usize = 8 +
+        32 +  // betting_pool: Pubkey
+        4 + MAX_MODERATORS as usize * ModeratorVote::LEN +  // votes
+        1;    // bump: u8
+
+    pub fn has_voted(&self, moderator: &Pubkey) -> bool {
+        self.votes.iter().any(|vote| vote.moderator == *moderator)
+    }
+
+    /// Number of votes cast for `outcome`
+    pub fn votes_for(&self, outcome: u8) -> u8 {
+        self.votes.iter().filter(|vote| vote.outcome == outcome).count() as u8
+    }
+
+    /// Whether any two votes disagree
+    pub fn is_conflicting(&self) -> bool {
+        self.votes.windows(2).any(|pair| pair[0].outcome != pair[1].outcome)
+    }
+}
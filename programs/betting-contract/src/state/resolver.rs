@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::error::BettingError;
+
+/// Length of the account discriminator that precedes a resolver account's fields
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// How a resolver account's data maps to a winning outcome
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResolverKind {
+    /// A result written by another program.
+    /// Layout after the discriminator: `resolved: bool`, `outcome: u8`.
+    /// The outcome is used as is, so it can also be void or draw.
+    ResultAccount,
+    /// A numeric feed for a two-outcome pool.
+    /// Layout after the discriminator: `value: i64`, `updated_at: i64`.
+    /// Outcome 1 wins if the value is at least `threshold`, outcome 2 otherwise.
+    /// Only readings taken after the pool locked count.
+    ValueFeed { threshold: i64 },
+}
+
+/// An on-chain account a pool's result is read from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResolverConfig {
+    pub kind: ResolverKind,
+    /// The account holding the result
+    pub account: Pubkey,
+    /// Program that must own `account`, so nobody else can write it
+    pub owner: Pubkey,
+}
+
+impl ResolverConfig {
+    pub const LEN: usize =
+        1 + 8 + // kind (largest variant)
+        32 + // account
+        32; // owner
+
+    /// Read the winning outcome from the resolver account's `data`.
+    /// Returns `None` while the result is not available yet.
+    pub fn read_outcome(&self, data: &[u8], locked_at: i64) -> Result<Option<u8>> {
+        let fields = data.get(DISCRIMINATOR_LEN..).ok_or(BettingError::InvalidResolverAccount)?;
+        match self.kind {
+            ResolverKind::ResultAccount => {
+                let [resolved, outcome] = read_array::<2>(fields)?;
+                Ok((resolved != 0).then_some(outcome))
+            }
+            ResolverKind::ValueFeed { threshold } => {
+                let value = i64::from_le_bytes(read_array::<8>(fields)?);
+                let updated_at = i64::from_le_bytes(read_array::<8>(&fields[8..])?);
+                if updated_at < locked_at {
+                    return Ok(None);
+                }
+                Ok(Some(if value >= threshold { 1 } else { 2 }))
+            }
+        }
+    }
+
+    /// Check the resolver can produce a result for a pool with `outcome_count` outcomes
+    pub fn validate(&self, outcome_count: usize) -> Result<()> {
+        if let ResolverKind::ValueFeed { .. } = self.kind {
+            require!(outcome_count == 2, BettingError::InvalidResolver);
+        }
+        require!(self.owner != crate::ID, BettingError::InvalidResolver);
+        Ok(())
+    }
+}
+
+fn read_array<const N: usize>(data: &[u8]) -> Result<[u8; N]> {
+    data.get(..N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(BettingError::InvalidResolverAccount.into())
+}
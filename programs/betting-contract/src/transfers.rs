@@ -0,0 +1,253 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_interface::{
+    self, CloseAccount, HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::*;
+use crate::error::BettingError;
+
+/// Token accounts needed to move funds in or out of an SPL token pool
+pub struct TokenTransferAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    /// Source of a deposit or destination of a payout
+    pub user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> TokenTransferAccounts<'a, 'info> {
+    /// Collect an instruction's optional token accounts.
+    /// Returns `None` for SOL pools and requires every account for token pools.
+    pub fn for_pool(
+        betting_pool: &BettingPool,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        token_vault: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        user_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Option<Self>> {
+        let Some(pool_mint) = betting_pool.mint else {
+            return Ok(None);
+        };
+        match (mint, token_vault, user_token_account, token_program) {
+            (Some(mint), Some(token_vault), Some(user_token_account), Some(token_program)) => {
+                require_keys_eq!(mint.key(), pool_mint, BettingError::InvalidMint);
+                Ok(Some(Self {
+                    mint,
+                    token_vault,
+                    user_token_account,
+                    token_program,
+                }))
+            }
+            _ => err!(BettingError::MissingTokenAccounts),
+        }
+    }
+}
+
+/// Whether `mint` is a Token-2022 mint with a transfer fee. A fee currently set to zero counts,
+/// its authority can raise it at any time
+pub fn has_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension_types()?.contains(&ExtensionType::TransferFeeConfig))
+}
+
+/// Move a bet from the user into the pool's vault.
+/// Returns the amount the pool actually received, which is less than `amount`
+/// for Token-2022 mints that charge a transfer fee.
+pub fn deposit_to_pool<'info>(
+    vault: &SystemAccount<'info>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token: Option<TokenTransferAccounts<'_, 'info>>,
+    amount: u64,
+) -> Result<u64> {
+    let Some(token) = token else {
+        let transfer_instruction = system_program::Transfer {
+            from: user.to_account_info(),
+            to: vault.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(system_program.to_account_info(), transfer_instruction);
+        system_program::transfer(cpi_context, amount)?;
+        return Ok(amount);
+    };
+
+    let vault_info = token.token_vault.to_account_info();
+    let balance_before = token_balance(&vault_info)?;
+    let transfer_instruction = TransferChecked {
+        from: token.user_token_account.to_account_info(),
+        mint: token.mint.to_account_info(),
+        to: vault_info.clone(),
+        authority: user.to_account_info(),
+    };
+    let cpi_context = CpiContext::new(token.token_program.to_account_info(), transfer_instruction);
+    token_interface::transfer_checked(cpi_context, amount, token.mint.decimals)?;
+    let balance_after = token_balance(&vault_info)?;
+
+    balance_after
+        .checked_sub(balance_before)
+        .ok_or(BettingError::ArithmeticOverflow.into())
+}
+
+/// Move `amount` out of the pool's vault to `recipient` (or to the recipient's token account for token pools)
+pub fn pay_from_pool<'info>(
+    betting_pool: &Account<'info, BettingPool>,
+    vault: &SystemAccount<'info>,
+    recipient: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token: Option<TokenTransferAccounts<'_, 'info>>,
+    amount: u64,
+) -> Result<()> {
+    let Some(token) = token else {
+        // The vault keeps its rent-exempt minimum until the pool is closed
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        if vault.lamports().saturating_sub(rent_exempt_minimum) < amount {
+            return Err(BettingError::InsufficientFunds.into());
+        }
+        let betting_pool_key = betting_pool.key();
+        let bump = [betting_pool.vault_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", betting_pool_key.as_ref(), &bump]];
+        let transfer_instruction = system_program::Transfer {
+            from: vault.to_account_info(),
+            to: recipient.clone(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            transfer_instruction,
+            signer_seeds,
+        );
+        return system_program::transfer(cpi_context, amount);
+    };
+
+    require_keys_eq!(
+        token.user_token_account.owner,
+        recipient.key(),
+        BettingError::InvalidTokenAccount
+    );
+    if token_balance(&token.token_vault.to_account_info())? < amount {
+        return Err(BettingError::InsufficientFunds.into());
+    }
+
+    let match_number_seed = betting_pool.match_number.to_le_bytes();
+    let bump = [betting_pool.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"betting_pool",
+        betting_pool.creator.as_ref(),
+        betting_pool.stream_id.as_bytes(),
+        &match_number_seed,
+        &bump,
+    ]];
+    let transfer_instruction = TransferChecked {
+        from: token.token_vault.to_account_info(),
+        mint: token.mint.to_account_info(),
+        to: token.user_token_account.to_account_info(),
+        authority: betting_pool.to_account_info(),
+    };
+    let cpi_context = CpiContext::new_with_signer(
+        token.token_program.to_account_info(),
+        transfer_instruction,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_context, amount, token.mint.decimals)
+}
+
+/// Amount left in the pool's vault above what it needs to stay open
+pub fn vault_surplus(vault: &SystemAccount, token: Option<&TokenTransferAccounts>) -> Result<u64> {
+    match token {
+        Some(token) => token_balance(&token.token_vault.to_account_info()),
+        None => Ok(vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0))),
+    }
+}
+
+/// What a legacy pool holds above its rent-exempt minimum: its stakes and unpaid fees
+pub fn legacy_pool_surplus(betting_pool: &Account<LegacyBettingPool>) -> Result<u64> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(betting_pool.to_account_info().data_len());
+    Ok(betting_pool.to_account_info().lamports().saturating_sub(rent_exempt_minimum))
+}
+
+/// Move `amount` out of a legacy pool, which holds its stakes in its own lamports
+pub fn pay_from_legacy_pool(
+    betting_pool: &Account<LegacyBettingPool>,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    if legacy_pool_surplus(betting_pool)? < amount {
+        return Err(BettingError::InsufficientFunds.into());
+    }
+    betting_pool.sub_lamports(amount)?;
+    recipient.add_lamports(amount)?;
+    Ok(())
+}
+
+/// Empty the pool's vaults into `destination`: the SOL vault's remaining lamports,
+/// and for token pools the token vault's rent (the token vault must hold no tokens).
+/// Transfer fees withheld in a Token-2022 vault are first harvested to the mint, where the
+/// mint's withdraw authority collects them
+pub fn close_vaults<'info>(
+    betting_pool: &Account<'info, BettingPool>,
+    vault: &SystemAccount<'info>,
+    destination: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token: Option<TokenTransferAccounts<'_, 'info>>,
+) -> Result<()> {
+    if let Some(token) = token {
+        // Token-2022 refuses to close an account still holding withheld fees
+        if has_transfer_fee(token.mint)? {
+            let harvest_instruction = HarvestWithheldTokensToMint {
+                token_program_id: token.token_program.to_account_info(),
+                mint: token.mint.to_account_info(),
+            };
+            let cpi_context = CpiContext::new(token.token_program.to_account_info(), harvest_instruction);
+            token_interface::harvest_withheld_tokens_to_mint(cpi_context, vec![token.token_vault.to_account_info()])?;
+        }
+
+        let match_number_seed = betting_pool.match_number.to_le_bytes();
+        let bump = [betting_pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"betting_pool",
+            betting_pool.creator.as_ref(),
+            betting_pool.stream_id.as_bytes(),
+            &match_number_seed,
+            &bump,
+        ]];
+        let close_instruction = CloseAccount {
+            account: token.token_vault.to_account_info(),
+            destination: destination.clone(),
+            authority: betting_pool.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            token.token_program.to_account_info(),
+            close_instruction,
+            signer_seeds,
+        );
+        token_interface::close_account(cpi_context)?;
+    }
+
+    let lamports = vault.lamports();
+    if lamports == 0 {
+        return Ok(());
+    }
+    let betting_pool_key = betting_pool.key();
+    let bump = [betting_pool.vault_bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"vault", betting_pool_key.as_ref(), &bump]];
+    let transfer_instruction = system_program::Transfer {
+        from: vault.to_account_info(),
+        to: destination.clone(),
+    };
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        transfer_instruction,
+        signer_seeds,
+    );
+    system_program::transfer(cpi_context, lamports)
+}
+
+fn token_balance(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
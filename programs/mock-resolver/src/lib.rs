@@ -0,0 +1,106 @@
+//! Stand-in for an oracle or a results program, used to test `resolve_from_oracle` locally.
+//! Its accounts follow the layouts the betting contract's `ResolverKind` variants expect.
+
+use anchor_lang::prelude::*;
+
+declare_id!("2HHxB1uueBrC6W7ESPm3hz9ajFpNheAFTg8ySPYPjpaL");
+
+// `#[program]` generates Anchor's IDL handlers, which still call the deprecated
+// `AccountInfo::realloc`; only this module allows it.
+#[allow(deprecated)]
+mod program_module {
+    use super::*;
+
+    #[program]
+    pub mod mock_resolver {
+        use super::*;
+
+        pub fn create_result(ctx: Context<CreateResult>) -> Result<()> {
+            let result = &mut ctx.accounts.result;
+            result.resolved = false;
+            result.outcome = 0;
+            result.authority = ctx.accounts.authority.key();
+            Ok(())
+        }
+
+        pub fn set_result(ctx: Context<SetResult>, outcome: u8) -> Result<()> {
+            let result = &mut ctx.accounts.result;
+            result.resolved = true;
+            result.outcome = outcome;
+            Ok(())
+        }
+
+        pub fn create_feed(ctx: Context<CreateFeed>) -> Result<()> {
+            let feed = &mut ctx.accounts.feed;
+            feed.value = 0;
+            feed.updated_at = 0;
+            feed.authority = ctx.accounts.authority.key();
+            Ok(())
+        }
+
+        pub fn set_value(ctx: Context<SetValue>, value: i64) -> Result<()> {
+            let feed = &mut ctx.accounts.feed;
+            feed.value = value;
+            feed.updated_at = Clock::get()?.unix_timestamp;
+            Ok(())
+        }
+    }
+}
+
+pub use program_module::*;
+
+#[derive(Accounts)]
+pub struct CreateResult<'info> {
+    #[account(init, payer = authority, space = ResultAccount::LEN)]
+    pub result: Account<'info, ResultAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetResult<'info> {
+    #[account(mut, has_one = authority)]
+    pub result: Account<'info, ResultAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateFeed<'info> {
+    #[account(init, payer = authority, space = FeedAccount::LEN)]
+    pub feed: Account<'info, FeedAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetValue<'info> {
+    #[account(mut, has_one = authority)]
+    pub feed: Account<'info, FeedAccount>,
+    pub authority: Signer<'info>,
+}
+
+/// `ResolverKind::ResultAccount` layout
+#[account]
+pub struct ResultAccount {
+    pub resolved: bool,
+    pub outcome: u8,
+    pub authority: Pubkey,
+}
+
+impl ResultAccount {
+    pub const LEN: usize = 8 + 1 + 1 + 32;
+}
+
+/// `ResolverKind::ValueFeed` layout
+#[account]
+pub struct FeedAccount {
+    pub value: i64,
+    pub updated_at: i64,
+    pub authority: Pubkey,
+}
+
+impl FeedAccount {
+    pub const LEN: usize = 8 + 8 + 8 + 32;
+}
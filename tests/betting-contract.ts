@@ -1,8 +1,30 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BettingContract } from "../target/types/betting_contract";
+import { MockResolver } from "../target/types/mock_resolver";
 import { expect } from "chai";
-import { PublicKey, SystemProgram, LAMPORTS_PER_SOL, Transaction } from "@solana/web3.js";
+import {
+  Ed25519Program,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAccount,
+  getMint,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  getTransferFeeConfig,
+  mintTo,
+} from "@solana/spl-token";
 import * as fs from "fs";
 import * as path from "path";
 
@@ -10,6 +32,7 @@ describe("betting-contract", () => {
   // Configure the client
   anchor.setProvider(anchor.AnchorProvider.env());
   const program = anchor.workspace.BettingContract as Program<BettingContract>;
+  const mockResolver = anchor.workspace.MockResolver as Program<MockResolver>;
   const provider = anchor.AnchorProvider.env();
 
   // Bank account - This will be your pre-funded account that distributes funds
@@ -27,12 +50,18 @@ describe("betting-contract", () => {
   const BET_AMOUNT_1_SOL = new anchor.BN(LAMPORTS_PER_SOL);
   const BET_AMOUNT_2_SOL = new anchor.BN(2 * LAMPORTS_PER_SOL);
   const BET_AMOUNT_HALF_SOL = new anchor.BN(0.5 * LAMPORTS_PER_SOL);
+  const MATCH_NUMBER = new anchor.BN(0);
+
+  // Program config
+  let configPda: PublicKey;
+  let platformTreasury: PublicKey;
 
   // PDAs
   let bettingPoolPda: PublicKey;
-  let user1BetPda: PublicKey;
-  let user2BetPda: PublicKey;
-  let user3BetPda: PublicKey;
+  let vaultPda: PublicKey;
+  let user1PositionPda: PublicKey;
+  let user2PositionPda: PublicKey;
+  let user3PositionPda: PublicKey;
 
   /**
    * Transfer SOL from bank account to a recipient
@@ -55,6 +84,66 @@ describe("betting-contract", () => {
     }
   }
 
+  /**
+   * Derive a pool PDA, seeded by creator, stream id and match number
+   */
+  function deriveBettingPoolPda(
+    creator: PublicKey,
+    streamId: string,
+    matchNumber: anchor.BN = MATCH_NUMBER
+  ): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("betting_pool"),
+        creator.toBuffer(),
+        Buffer.from(streamId),
+        matchNumber.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    return pda;
+  }
+
+  /**
+   * Derive a user's position PDA for one outcome of a pool
+   */
+  function derivePositionPda(bettingPool: PublicKey, user: PublicKey, prediction: number): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        bettingPool.toBuffer(),
+        user.toBuffer(),
+        Buffer.from([prediction]),
+      ],
+      program.programId
+    );
+    return pda;
+  }
+
+  /**
+   * Derive a legacy pool PDA, seeded by stream id only (pools created before creator namespacing)
+   */
+  function deriveLegacyBettingPoolPda(streamId: string): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("betting_pool"), Buffer.from(streamId)],
+      program.programId
+    );
+    return pda;
+  }
+
+  /**
+   * Derive the PDA of a legacy pool's bet
+   */
+  function deriveLegacyBetPda(bettingPool: PublicKey, user: PublicKey, betIndex: number): PublicKey {
+    const betIndexBuffer = Buffer.alloc(4);
+    betIndexBuffer.writeUInt32LE(betIndex);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bet"), bettingPool.toBuffer(), user.toBuffer(), betIndexBuffer],
+      program.programId
+    );
+    return pda;
+  }
+
   /**
    * Load or create the bank keypair
    */
@@ -144,41 +233,59 @@ describe("betting-contract", () => {
     console.log(`✅ Admin funded: ${adminBalance / LAMPORTS_PER_SOL} SOL`);
     console.log(`✅ User1 funded: ${user1Balance / LAMPORTS_PER_SOL} SOL\n`);
 
-    // Derive PDAs
-    [bettingPoolPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("betting_pool"), Buffer.from(STREAM_ID)],
+    // Create the program config on first run (the provider wallet is the upgrade authority)
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
       program.programId
     );
 
-    [user1BetPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("bet"),
-        bettingPoolPda.toBuffer(),
-        user1.publicKey.toBuffer(),
-        Buffer.from([0, 0, 0, 0])
-      ],
-      program.programId
-    );
+    let config = await program.account.programConfig.fetchNullable(configPda);
+    if (!config) {
+      const [programDataPda] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
 
-    [user2BetPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("bet"),
-        bettingPoolPda.toBuffer(),
-        user2.publicKey.toBuffer(),
-        Buffer.from([1, 0, 0, 0])
-      ],
-      program.programId
-    );
+      await program.methods
+        .initializeConfig({
+          authority: provider.wallet.publicKey,
+          platformTreasury: anchor.web3.Keypair.generate().publicKey,
+          platformFeeRate: 250,
+          defaultCreatorFeeRate: 250,
+          maxCreatorFeeRate: 1000,
+          minBet: new anchor.BN(0.01 * LAMPORTS_PER_SOL),
+          maxBet: new anchor.BN(100 * LAMPORTS_PER_SOL),
+          paused: false,
+          minResolutionDelay: new anchor.BN(0),
+          disputeWindow: new anchor.BN(0),
+          disputeBond: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+          arbiter: provider.wallet.publicKey,
+          cancellationCutoff: new anchor.BN(0),
+          guardian: provider.wallet.publicKey,
+          maxDeadlineExtension: new anchor.BN(24 * 60 * 60),
+        })
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          programData: programDataPda,
+        })
+        .rpc();
 
-    [user3BetPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("bet"),
-        bettingPoolPda.toBuffer(),
-        user3.publicKey.toBuffer(),
-        Buffer.from([2, 0, 0, 0])
-      ],
+      config = await program.account.programConfig.fetch(configPda);
+    }
+    platformTreasury = config.platformTreasury;
+
+    // Derive PDAs
+    bettingPoolPda = deriveBettingPoolPda(admin.publicKey, STREAM_ID);
+
+    [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), bettingPoolPda.toBuffer()],
       program.programId
     );
+
+    user1PositionPda = derivePositionPda(bettingPoolPda, user1.publicKey, 1);
+    user2PositionPda = derivePositionPda(bettingPoolPda, user2.publicKey, 2);
+    user3PositionPda = derivePositionPda(bettingPoolPda, user3.publicKey, 1);
   });
 
   describe("Initialize Betting Pool", () => {
@@ -187,7 +294,7 @@ describe("betting-contract", () => {
       const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
       
       const tx = await program.methods
-        .initialize(STREAM_ID, new anchor.BN(bettingDeadline), moderator.publicKey)
+        .initialize(STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
         .accountsPartial({
           bettingPool: bettingPoolPda,
           admin: admin.publicKey,
@@ -203,26 +310,64 @@ describe("betting-contract", () => {
       expect(bettingPool.moderator.toString()).to.equal(moderator.publicKey.toString());
       expect(bettingPool.streamId).to.equal(STREAM_ID);
       expect(bettingPool.totalPool.toNumber()).to.equal(0);
-      expect(bettingPool.player1Bets.toNumber()).to.equal(0);
-      expect(bettingPool.player2Bets.toNumber()).to.equal(0);
-      expect(bettingPool.winnerDeclared).to.be.false;
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(0);
+      expect(bettingPool.outcomes[1].totalBets.toNumber()).to.equal(0);
+      expect(bettingPool.status).to.deep.equal({ open: {} });
       expect(bettingPool.creatorFeeRate).to.equal(250); // 2.5%
       expect(bettingPool.platformFeeRate).to.equal(250); // 2.5%
-      expect(bettingPool.isPayoutComplete).to.be.false;
+      expect(bettingPool.creator.toString()).to.equal(admin.publicKey.toString());
+      expect(bettingPool.matchNumber.toNumber()).to.equal(0);
+    });
+
+    it("Lets another creator use the same stream id", async () => {
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      const otherPoolPda = deriveBettingPoolPda(user2.publicKey, STREAM_ID);
+      expect(otherPoolPda.toString()).to.not.equal(bettingPoolPda.toString());
+
+      await program.methods
+        .initialize(STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: otherPoolPda,
+          admin: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+
+      const otherPool = await program.account.bettingPool.fetch(otherPoolPda);
+      expect(otherPool.creator.toString()).to.equal(user2.publicKey.toString());
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      expect(bettingPool.admin.toString()).to.equal(admin.publicKey.toString());
+    });
+
+    it("Lets a creator run a second match on the same stream", async () => {
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      const rematchNumber = new anchor.BN(1);
+      const rematchPoolPda = deriveBettingPoolPda(admin.publicKey, STREAM_ID, rematchNumber);
+
+      await program.methods
+        .initialize(STREAM_ID, rematchNumber, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: rematchPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const rematchPool = await program.account.bettingPool.fetch(rematchPoolPda);
+      expect(rematchPool.matchNumber.toNumber()).to.equal(1);
     });
 
     it("Fails to create pool with non-admin", async () => {
       // Create a pool with user1 as admin
       const streamId2 = "test-stream-456";
       const bettingDeadline2 = Math.floor(Date.now() / 1000) + (60 * 60);
-      const [poolPda2] = PublicKey.findProgramAddressSync(
-        [Buffer.from("betting_pool"), Buffer.from(streamId2)],
-        program.programId
-      );
+      const poolPda2 = deriveBettingPoolPda(user1.publicKey, streamId2);
 
       // User1 creates a pool (this should succeed now)
       await program.methods
-        .initialize(streamId2, new anchor.BN(bettingDeadline2), moderator.publicKey)
+        .initialize(streamId2, MATCH_NUMBER, new anchor.BN(bettingDeadline2), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
         .accountsPartial({
           bettingPool: poolPda2,
           admin: user1.publicKey,
@@ -256,7 +401,7 @@ describe("betting-contract", () => {
         .placeBet(1, BET_AMOUNT_1_SOL)
         .accountsPartial({
           bettingPool: bettingPoolPda,
-          bet: user1BetPda,
+          position: user1PositionPda,
           user: user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -265,16 +410,22 @@ describe("betting-contract", () => {
 
       console.log("User1 bet transaction:", tx);
 
-      const bet = await program.account.bet.fetch(user1BetPda);
-      expect(bet.user.toString()).to.equal(user1.publicKey.toString());
-      expect(bet.amount.toNumber()).to.equal(LAMPORTS_PER_SOL);
-      expect(bet.prediction).to.equal(1);
-      expect(bet.isPaidOut).to.be.false;
+      const position = await program.account.position.fetch(user1PositionPda);
+      expect(position.user.toString()).to.equal(user1.publicKey.toString());
+      expect(position.amount.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(position.prediction).to.equal(1);
+      expect(position.betCount).to.equal(1);
+      expect(position.firstBetAt.toNumber()).to.equal(position.lastBetAt.toNumber());
+      expect(position.isPaidOut).to.be.false;
 
       const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
       expect(bettingPool.totalPool.toNumber()).to.equal(LAMPORTS_PER_SOL);
-      expect(bettingPool.player1Bets.toNumber()).to.equal(LAMPORTS_PER_SOL);
-      expect(bettingPool.player1BetCount).to.equal(1);
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[0].betCount).to.equal(1);
+
+      const rentExemptMinimum = await provider.connection.getMinimumBalanceForRentExemption(0);
+      const vaultBalance = await provider.connection.getBalance(vaultPda);
+      expect(vaultBalance).to.equal(rentExemptMinimum + LAMPORTS_PER_SOL);
 
       const finalBalance = await provider.connection.getBalance(user1.publicKey);
       expect(finalBalance).to.be.lessThan(initialBalance - LAMPORTS_PER_SOL);
@@ -285,7 +436,7 @@ describe("betting-contract", () => {
         .placeBet(2, BET_AMOUNT_2_SOL)
         .accountsPartial({
           bettingPool: bettingPoolPda,
-          bet: user2BetPda,
+          position: user2PositionPda,
           user: user2.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -296,9 +447,9 @@ describe("betting-contract", () => {
 
       const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
       expect(bettingPool.totalPool.toNumber()).to.equal(3 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player1Bets.toNumber()).to.equal(LAMPORTS_PER_SOL);
-      expect(bettingPool.player2Bets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player2BetCount).to.equal(1);
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].totalBets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].betCount).to.equal(1);
     });
 
     it("User3 places bet on Player 1", async () => {
@@ -306,7 +457,7 @@ describe("betting-contract", () => {
         .placeBet(1, BET_AMOUNT_HALF_SOL)
         .accountsPartial({
           bettingPool: bettingPoolPda,
-          bet: user3BetPda,
+          position: user3PositionPda,
           user: user3.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -317,32 +468,21 @@ describe("betting-contract", () => {
 
       const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
       expect(bettingPool.totalPool.toNumber()).to.equal(3.5 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player1Bets.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player2Bets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player1BetCount).to.equal(2);
-      expect(bettingPool.player2BetCount).to.equal(1);
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].totalBets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[0].betCount).to.equal(2);
+      expect(bettingPool.outcomes[1].betCount).to.equal(1);
     });
 
     it("Fails to place bet with invalid prediction", async () => {
-      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
-      const currentBetCount = bettingPool.player1BetCount + bettingPool.player2BetCount;
-      
-      const [invalidBetPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("bet"),
-          bettingPoolPda.toBuffer(),
-          user1.publicKey.toBuffer(),
-          Buffer.from(new anchor.BN(currentBetCount).toArrayLike(Buffer, 'le', 4))
-        ],
-        program.programId
-      );
+      const invalidPositionPda = derivePositionPda(bettingPoolPda, user1.publicKey, 3);
 
       try {
         await program.methods
           .placeBet(3, BET_AMOUNT_1_SOL)
           .accountsPartial({
             bettingPool: bettingPoolPda,
-            bet: invalidBetPda,
+            position: invalidPositionPda,
             user: user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -356,6 +496,53 @@ describe("betting-contract", () => {
   });
 
   describe("Declare Winner", () => {
+    it("Fails to declare a winner while betting is open", async () => {
+      try {
+        await program.methods
+          .declareWinner(1)
+          .accountsPartial({
+            bettingPool: bettingPoolPda,
+            signer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the pool is not locked");
+      } catch (error) {
+        expect(error.message).to.include("PoolNotLocked");
+      }
+    });
+
+    it("Fails to lock the pool early as a bettor", async () => {
+      try {
+        await program.methods
+          .lockPool()
+          .accountsPartial({
+            bettingPool: bettingPoolPda,
+            signer: user1.publicKey,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - only the admin or moderator can lock before the deadline");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("Moderator locks the pool when the match starts", async () => {
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: bettingPoolPda,
+          signer: moderator.publicKey,
+        })
+        .signers([moderator])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      expect(bettingPool.status).to.deep.equal({ locked: {} });
+      expect(bettingPool.lockedAt.toNumber()).to.be.greaterThan(0);
+    });
+
     it("Admin declares Player 1 as winner", async () => {
       const tx = await program.methods
         .declareWinner(1)
@@ -369,7 +556,7 @@ describe("betting-contract", () => {
       console.log("Declare winner transaction (admin):", tx);
 
       const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
-      expect(bettingPool.winnerDeclared).to.be.true;
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
       expect(bettingPool.winningOutcome).to.equal(1);
     });
 
@@ -410,14 +597,14 @@ describe("betting-contract", () => {
     it("Pays out User1 (winner with 1 SOL bet)", async () => {
       const initialBalance = await provider.connection.getBalance(user1.publicKey);
       const initialPoolBalance = await provider.connection.getBalance(bettingPoolPda);
+      const initialVaultBalance = await provider.connection.getBalance(vaultPda);
 
       const tx = await program.methods
         .payoutWinners()
         .accountsPartial({
           bettingPool: bettingPoolPda,
-          bet: user1BetPda,
+          position: user1PositionPda,
           winner: user1.publicKey,
-          platformTreasury: admin.publicKey,
           payer: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -426,14 +613,20 @@ describe("betting-contract", () => {
 
       console.log("User1 payout transaction:", tx);
 
-      const bet = await program.account.bet.fetch(user1BetPda);
-      expect(bet.isPaidOut).to.be.true;
+      // The settled position is closed and its rent returned to the winner
+      expect(await provider.connection.getAccountInfo(user1PositionPda)).to.be.null;
+
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      expect(bettingPool.status).to.deep.equal({ settling: {} });
 
       const finalBalance = await provider.connection.getBalance(user1.publicKey);
       const finalPoolBalance = await provider.connection.getBalance(bettingPoolPda);
+      const finalVaultBalance = await provider.connection.getBalance(vaultPda);
 
       expect(finalBalance).to.be.greaterThan(initialBalance);
-      expect(finalPoolBalance).to.be.lessThan(initialPoolBalance);
+      expect(finalVaultBalance).to.be.lessThan(initialVaultBalance);
+      // Stakes live in the vault, the pool account keeps only its rent
+      expect(finalPoolBalance).to.equal(initialPoolBalance);
     });
 
     it("Pays out User3 (winner with 0.5 SOL bet)", async () => {
@@ -443,9 +636,8 @@ describe("betting-contract", () => {
         .payoutWinners()
         .accountsPartial({
           bettingPool: bettingPoolPda,
-          bet: user3BetPda,
+          position: user3PositionPda,
           winner: user3.publicKey,
-          platformTreasury: admin.publicKey,
           payer: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -454,8 +646,8 @@ describe("betting-contract", () => {
 
       console.log("User3 payout transaction:", tx);
 
-      const bet = await program.account.bet.fetch(user3BetPda);
-      expect(bet.isPaidOut).to.be.true;
+      // The settled position is closed and its rent returned to the winner
+      expect(await provider.connection.getAccountInfo(user3PositionPda)).to.be.null;
 
       const finalBalance = await provider.connection.getBalance(user3.publicKey);
       expect(finalBalance).to.be.greaterThan(initialBalance);
@@ -467,10 +659,9 @@ describe("betting-contract", () => {
           .payoutWinners()
           .accountsPartial({
             bettingPool: bettingPoolPda,
-            bet: user2BetPda,
+            position: user2PositionPda,
             winner: user2.publicKey,
-            platformTreasury: admin.publicKey,
-            payer: admin.publicKey,
+              payer: admin.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([admin])
@@ -487,42 +678,89 @@ describe("betting-contract", () => {
           .payoutWinners()
           .accountsPartial({
             bettingPool: bettingPoolPda,
-            bet: user1BetPda,
+            position: user1PositionPda,
             winner: user1.publicKey,
-            platformTreasury: admin.publicKey,
-            payer: admin.publicKey,
+              payer: admin.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([admin])
           .rpc();
         expect.fail("Should have failed - bet already paid out");
       } catch (error) {
-        expect(error.message).to.include("BetAlreadyPaidOut");
+        expect(error.message).to.include("AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("Creator & Platform Fees", () => {
+    it("Pays the creator fee once", async () => {
+      const initialBalance = await provider.connection.getBalance(admin.publicKey);
+
+      await program.methods
+        .payoutCreator()
+        .accountsPartial({
+          bettingPool: bettingPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      const expectedFee = Math.floor((3.5 * LAMPORTS_PER_SOL * bettingPool.creatorFeeRate) / 10000);
+      expect(bettingPool.creatorFeePaid.toNumber()).to.equal(expectedFee);
+
+      const finalBalance = await provider.connection.getBalance(admin.publicKey);
+      expect(finalBalance).to.be.greaterThan(initialBalance);
+    });
+
+    it("Fails to pay the creator fee twice", async () => {
+      try {
+        await program.methods
+          .payoutCreator()
+          .accountsPartial({
+            bettingPool: bettingPoolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - creator fee already paid");
+      } catch (error) {
+        expect(error.message).to.include("FeeAlreadyPaid");
       }
     });
+
+    it("Pays the platform fee and completes the pool", async () => {
+      await program.methods
+        .payoutPlatform()
+        .accountsPartial({
+          bettingPool: bettingPoolPda,
+          platformWallet: platformTreasury,
+          authority: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      expect(bettingPool.platformFeePaid.toNumber()).to.be.greaterThan(0);
+      expect(bettingPool.remainingClaims).to.equal(0);
+      expect(bettingPool.remainingClaimable.toNumber()).to.equal(0);
+      expect(bettingPool.status).to.deep.equal({ settled: {} });
+    });
   });
 
   describe("Edge Cases", () => {
     it("Cannot place bet after winner declared", async () => {
-      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
-      const currentBetCount = bettingPool.player1BetCount + bettingPool.player2BetCount;
-      
-      const [lateBetPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("bet"),
-          bettingPoolPda.toBuffer(),
-          user1.publicKey.toBuffer(),
-          Buffer.from(new anchor.BN(currentBetCount).toArrayLike(Buffer, 'le', 4))
-        ],
-        program.programId
-      );
+      const latePositionPda = derivePositionPda(bettingPoolPda, user1.publicKey, 1);
 
       try {
         await program.methods
           .placeBet(1, BET_AMOUNT_1_SOL)
           .accountsPartial({
             bettingPool: bettingPoolPda,
-            bet: lateBetPda,
+            position: latePositionPda,
             user: user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -542,23 +780,2772 @@ describe("betting-contract", () => {
       console.log("\n=== Final Pool Statistics ===");
       console.log("Stream ID:", bettingPool.streamId);
       console.log("Total Pool:", bettingPool.totalPool.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      console.log("Player 1 Bets:", bettingPool.player1Bets.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      console.log("Player 2 Bets:", bettingPool.player2Bets.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      console.log("Player 1 Bet Count:", bettingPool.player1BetCount);
-      console.log("Player 2 Bet Count:", bettingPool.player2BetCount);
-      console.log("Winner Declared:", bettingPool.winnerDeclared);
+      console.log("Player 1 Bets:", bettingPool.outcomes[0].totalBets.toNumber() / LAMPORTS_PER_SOL, "SOL");
+      console.log("Player 2 Bets:", bettingPool.outcomes[1].totalBets.toNumber() / LAMPORTS_PER_SOL, "SOL");
+      console.log("Player 1 Bet Count:", bettingPool.outcomes[0].betCount);
+      console.log("Player 2 Bet Count:", bettingPool.outcomes[1].betCount);
+      console.log("Status:", Object.keys(bettingPool.status)[0]);
       console.log("Winning Outcome: Player", bettingPool.winningOutcome);
       console.log("Creator Fee Rate:", bettingPool.creatorFeeRate / 100, "%");
       console.log("Platform Fee Rate:", bettingPool.platformFeeRate / 100, "%");
       console.log("=============================\n");
 
       expect(bettingPool.totalPool.toNumber()).to.equal(3.5 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player1Bets.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player2Bets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
-      expect(bettingPool.player1BetCount).to.equal(2);
-      expect(bettingPool.player2BetCount).to.equal(1);
-      expect(bettingPool.winnerDeclared).to.be.true;
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].totalBets.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[0].betCount).to.equal(2);
+      expect(bettingPool.outcomes[1].betCount).to.equal(1);
+      expect(bettingPool.status).to.deep.equal({ settled: {} });
       expect(bettingPool.winningOutcome).to.equal(1);
     });
   });
+
+  describe("Closing Accounts", () => {
+    it("Fails to close a pool with open positions", async () => {
+      try {
+        await program.methods
+          .closePool()
+          .accountsPartial({
+            bettingPool: bettingPoolPda,
+            admin: admin.publicKey,
+            platformTreasury,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - User2's losing position is still open");
+      } catch (error) {
+        expect(error.message).to.include("PositionsStillOpen");
+      }
+    });
+
+    it("Fails to close a winning position as a losing bet", async () => {
+      const winningPositionPda = derivePositionPda(bettingPoolPda, user1.publicKey, 1);
+      try {
+        await program.methods
+          .closeLosingBet()
+          .accountsPartial({
+            bettingPool: bettingPoolPda,
+            position: winningPositionPda,
+            user: user1.publicKey,
+            payer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the position was already paid out and closed");
+      } catch (error) {
+        expect(error.message).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("Closes User2's losing position and returns the rent", async () => {
+      const initialBalance = await provider.connection.getBalance(user2.publicKey);
+      const positionRent = await provider.connection.getBalance(user2PositionPda);
+
+      await program.methods
+        .closeLosingBet()
+        .accountsPartial({
+          bettingPool: bettingPoolPda,
+          position: user2PositionPda,
+          user: user2.publicKey,
+          payer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(user2PositionPda)).to.be.null;
+      const finalBalance = await provider.connection.getBalance(user2.publicKey);
+      expect(finalBalance).to.equal(initialBalance + positionRent);
+
+      const bettingPool = await program.account.bettingPool.fetch(bettingPoolPda);
+      expect(bettingPool.closedPositions).to.equal(3);
+    });
+
+    it("Closes the settled pool", async () => {
+      const initialAdminBalance = await provider.connection.getBalance(admin.publicKey);
+      const poolRent = await provider.connection.getBalance(bettingPoolPda);
+      const rentExemptMinimum = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+      await program.methods
+        .closePool()
+        .accountsPartial({
+          bettingPool: bettingPoolPda,
+          admin: admin.publicKey,
+          platformTreasury,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(bettingPoolPda)).to.be.null;
+      expect(await provider.connection.getBalance(vaultPda)).to.equal(0);
+      const finalAdminBalance = await provider.connection.getBalance(admin.publicKey);
+      // Admin pays the transaction fee, so allow for it
+      expect(finalAdminBalance).to.be.at.least(initialAdminBalance + poolRent + rentExemptMinimum - 10_000);
+    });
+  });
+
+  describe("Cancel Pool & Refunds", () => {
+    const CANCEL_STREAM_ID = "cancel-stream-" + Date.now();
+    let cancelPoolPda: PublicKey;
+    let cancelPositionPda: PublicKey;
+
+    before(async () => {
+      cancelPoolPda = deriveBettingPoolPda(admin.publicKey, CANCEL_STREAM_ID);
+
+      cancelPositionPda = derivePositionPda(cancelPoolPda, user1.publicKey, 1);
+
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(CANCEL_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: cancelPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await program.methods
+        .placeBet(1, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: cancelPoolPda,
+          position: cancelPositionPda,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("Fails to refund before the pool is cancelled", async () => {
+      try {
+        await program.methods
+          .claimRefund()
+          .accountsPartial({
+            bettingPool: cancelPoolPda,
+            position: cancelPositionPda,
+            user: user1.publicKey,
+            payer: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - pool is not cancelled");
+      } catch (error) {
+        expect(error.message).to.include("RefundsNotAvailable");
+      }
+    });
+
+    it("Fails to cancel pool with unauthorized signer", async () => {
+      try {
+        await program.methods
+          .cancelPool()
+          .accountsPartial({
+            bettingPool: cancelPoolPda,
+            signer: user2.publicKey,
+          })
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have failed with unauthorized admin");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("Moderator cancels the pool", async () => {
+      const tx = await program.methods
+        .cancelPool()
+        .accountsPartial({
+          bettingPool: cancelPoolPda,
+          signer: moderator.publicKey,
+        })
+        .signers([moderator])
+        .rpc();
+
+      console.log("Cancel pool transaction:", tx);
+
+      const bettingPool = await program.account.bettingPool.fetch(cancelPoolPda);
+      expect(bettingPool.status).to.deep.equal({ cancelled: {} });
+    });
+
+    it("Refunds User1 in full", async () => {
+      const initialBalance = await provider.connection.getBalance(user1.publicKey);
+      const positionRent = await provider.connection.getBalance(cancelPositionPda);
+
+      const tx = await program.methods
+        .claimRefund()
+        .accountsPartial({
+          bettingPool: cancelPoolPda,
+          position: cancelPositionPda,
+          user: user1.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      console.log("User1 refund transaction:", tx);
+
+      expect(await provider.connection.getAccountInfo(cancelPositionPda)).to.be.null;
+
+      const finalBalance = await provider.connection.getBalance(user1.publicKey);
+      expect(finalBalance).to.equal(initialBalance + BET_AMOUNT_HALF_SOL.toNumber() + positionRent);
+    });
+
+    it("Fails to declare winner on a cancelled pool", async () => {
+      try {
+        await program.methods
+          .declareWinner(1)
+          .accountsPartial({
+            bettingPool: cancelPoolPda,
+            signer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - pool is cancelled");
+      } catch (error) {
+        expect(error.message).to.include("PoolCancelled");
+      }
+    });
+  });
+
+  describe("Void & Draw Outcomes", () => {
+    const VOID_STREAM_ID = "void-stream-" + Date.now();
+    let voidPoolPda: PublicKey;
+    let voidPositionPda: PublicKey;
+
+    before(async () => {
+      voidPoolPda = deriveBettingPoolPda(admin.publicKey, VOID_STREAM_ID);
+
+      voidPositionPda = derivePositionPda(voidPoolPda, user3.publicKey, 1);
+
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(VOID_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { feesOnDraw: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: voidPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      // One-sided pool: nobody backs Player 2
+      await program.methods
+        .placeBet(1, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: voidPoolPda,
+          position: voidPositionPda,
+          user: user3.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user3])
+        .rpc();
+    });
+
+    it("Admin locks the void pool", async () => {
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: voidPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Fails to declare an unbacked side as winner", async () => {
+      try {
+        await program.methods
+          .declareWinner(2)
+          .accountsPartial({
+            bettingPool: voidPoolPda,
+            signer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - no bets on winning outcome");
+      } catch (error) {
+        expect(error.message).to.include("NoBetsOnWinningOutcome");
+      }
+    });
+
+    it("Moderator voids the one-sided pool", async () => {
+      await program.methods
+        .declareWinner(0)
+        .accountsPartial({
+          bettingPool: voidPoolPda,
+          signer: moderator.publicKey,
+        })
+        .signers([moderator])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(voidPoolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(0);
+    });
+
+    it("Refunds User3 in full on a void pool", async () => {
+      const initialBalance = await provider.connection.getBalance(user3.publicKey);
+      const positionRent = await provider.connection.getBalance(voidPositionPda);
+
+      await program.methods
+        .claimRefund()
+        .accountsPartial({
+          bettingPool: voidPoolPda,
+          position: voidPositionPda,
+          user: user3.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const finalBalance = await provider.connection.getBalance(user3.publicKey);
+      expect(finalBalance).to.equal(initialBalance + BET_AMOUNT_HALF_SOL.toNumber() + positionRent);
+
+      // The only refund settles the fee-free void pool
+      const bettingPool = await program.account.bettingPool.fetch(voidPoolPda);
+      expect(bettingPool.status).to.deep.equal({ settled: {} });
+    });
+
+    it("Fails to pay creator fee on a fee-free void pool", async () => {
+      try {
+        await program.methods
+          .payoutCreator()
+          .accountsPartial({
+            bettingPool: voidPoolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - void pools pay no fees under this policy");
+      } catch (error) {
+        expect(error.message).to.include("NothingToPayout");
+      }
+    });
+  });
+
+  describe("Multi-Outcome Pools", () => {
+    const FFA_STREAM_ID = "ffa-stream-" + Date.now();
+    let ffaPoolPda: PublicKey;
+    let ffaPositionPda: PublicKey;
+
+    before(async () => {
+      ffaPoolPda = deriveBettingPoolPda(admin.publicKey, FFA_STREAM_ID);
+
+      ffaPositionPda = derivePositionPda(ffaPoolPda, user2.publicKey, 4);
+    });
+
+    it("Creates a free-for-all pool with four outcomes", async () => {
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(FFA_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Alice", "Bob", "Carol", "Dave"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(ffaPoolPda);
+      expect(bettingPool.outcomes.map((outcome) => outcome.label)).to.deep.equal(["Alice", "Bob", "Carol", "Dave"]);
+    });
+
+    it("Places a bet on the fourth outcome", async () => {
+      await program.methods
+        .placeBet(4, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          position: ffaPositionPda,
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(ffaPoolPda);
+      expect(bettingPool.outcomes[3].totalBets.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[3].betCount).to.equal(1);
+    });
+
+    it("Tops up an existing position", async () => {
+      const topUpAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+      await program.methods
+        .placeBet(4, topUpAmount)
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          position: ffaPositionPda,
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+
+      const position = await program.account.position.fetch(ffaPositionPda);
+      expect(position.amount.toNumber()).to.equal(0.6 * LAMPORTS_PER_SOL);
+      expect(position.betCount).to.equal(2);
+      expect(position.lastBetAt.toNumber()).to.be.at.least(position.firstBetAt.toNumber());
+
+      const bettingPool = await program.account.bettingPool.fetch(ffaPoolPda);
+      expect(bettingPool.outcomes[3].totalBets.toNumber()).to.equal(0.6 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[3].betCount).to.equal(2);
+      expect(bettingPool.outcomes[3].positionCount).to.equal(1);
+    });
+
+    it("Accepts bets from several users in the same slot", async () => {
+      const amount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+      const bettors = [user1, user3];
+      await Promise.all(
+        bettors.map((bettor) =>
+          program.methods
+            .placeBet(2, amount)
+            .accountsPartial({
+              bettingPool: ffaPoolPda,
+              position: derivePositionPda(ffaPoolPda, bettor.publicKey, 2),
+              user: bettor.publicKey,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([bettor])
+            .rpc()
+        )
+      );
+
+      const bettingPool = await program.account.bettingPool.fetch(ffaPoolPda);
+      expect(bettingPool.outcomes[1].betCount).to.equal(2);
+      const positions = await Promise.all(
+        bettors.map((bettor) => program.account.position.fetch(derivePositionPda(ffaPoolPda, bettor.publicKey, 2)))
+      );
+      expect(positions.map((position) => position.betIndex).sort()).to.deep.equal([2, 3]);
+    });
+
+    it("Fails to create a pool with a single outcome", async () => {
+      const streamId = "ffa-invalid-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+
+      try {
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Solo"], null, { parimutuel: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed with invalid outcome count");
+      } catch (error) {
+        expect(error.message).to.include("InvalidOutcomeCount");
+      }
+    });
+
+    it("Pays every winner in one batch", async () => {
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      await program.methods
+        .declareWinner(2)
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const winners = [user1, user3];
+      const batch = (bettors: anchor.web3.Keypair[], prediction: number) =>
+        bettors.flatMap((bettor) => [
+          { pubkey: derivePositionPda(ffaPoolPda, bettor.publicKey, prediction), isSigner: false, isWritable: true },
+          { pubkey: bettor.publicKey, isSigner: false, isWritable: true },
+        ]);
+
+      try {
+        await program.methods
+          .payoutWinnersBatch()
+          .accountsPartial({
+            bettingPool: ffaPoolPda,
+            payer: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(batch([user2], 4))
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - User2 backed a losing outcome");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBatchAccounts");
+      }
+
+      const initialBalances = await Promise.all(
+        winners.map((winner) => provider.connection.getBalance(winner.publicKey))
+      );
+
+      await program.methods
+        .payoutWinnersBatch()
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(batch(winners, 2))
+        .signers([admin])
+        .rpc();
+
+      for (const [index, winner] of winners.entries()) {
+        expect(await provider.connection.getAccountInfo(derivePositionPda(ffaPoolPda, winner.publicKey, 2))).to.be.null;
+        const finalBalance = await provider.connection.getBalance(winner.publicKey);
+        expect(finalBalance).to.be.greaterThan(initialBalances[index]);
+      }
+
+      const bettingPool = await program.account.bettingPool.fetch(ffaPoolPda);
+      expect(bettingPool.remainingClaims).to.equal(0);
+      expect(bettingPool.remainingClaimable.toNumber()).to.equal(0);
+    });
+
+    it("Skips already paid positions in a batch", async () => {
+      const initialBalance = await provider.connection.getBalance(user1.publicKey);
+
+      await program.methods
+        .payoutWinnersBatch()
+        .accountsPartial({
+          bettingPool: ffaPoolPda,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: derivePositionPda(ffaPoolPda, user1.publicKey, 2), isSigner: false, isWritable: true },
+          { pubkey: user1.publicKey, isSigner: false, isWritable: true },
+        ])
+        .signers([admin])
+        .rpc();
+
+      const finalBalance = await provider.connection.getBalance(user1.publicKey);
+      expect(finalBalance).to.equal(initialBalance);
+    });
+
+    it("Fails a batch with an empty account that isn't the winner's position", async () => {
+      try {
+        await program.methods
+          .payoutWinnersBatch()
+          .accountsPartial({
+            bettingPool: ffaPoolPda,
+            payer: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts([
+            { pubkey: anchor.web3.Keypair.generate().publicKey, isSigner: false, isWritable: true },
+            { pubkey: user1.publicKey, isSigner: false, isWritable: true },
+          ])
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the account is not User1's winning position");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBatchAccounts");
+      }
+    });
+  });
+
+  describe("Moderator Votes", () => {
+    async function createVotedPool(streamId: string): Promise<PublicKey> {
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .setModerators([moderator.publicKey, user3.publicKey, user1.publicKey], 2)
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      for (const prediction of [1, 2]) {
+        await program.methods
+          .placeBet(prediction, BET_AMOUNT_HALF_SOL)
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: derivePositionPda(poolPda, user2.publicKey, prediction),
+            user: user2.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user2])
+          .rpc();
+      }
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: moderator.publicKey,
+        })
+        .signers([moderator])
+        .rpc();
+      return poolPda;
+    }
+
+    function voteResult(poolPda: PublicKey, voter: anchor.web3.Keypair, outcome: number) {
+      return program.methods
+        .voteResult(outcome)
+        .accountsPartial({
+          bettingPool: poolPda,
+          moderator: voter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    it("Fails to set a threshold above the moderator set size", async () => {
+      const streamId = "votes-invalid-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      try {
+        await program.methods
+          .setModerators([moderator.publicKey, user3.publicKey], 3)
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed with an invalid moderator set");
+      } catch (error) {
+        expect(error.message).to.include("InvalidModeratorSet");
+      }
+    });
+
+    it("Finalizes the result once the threshold of moderators agree", async () => {
+      const poolPda = await createVotedPool("votes-agree-" + Date.now());
+
+      // A single key can no longer declare the result
+      try {
+        await program.methods
+          .declareWinner(1)
+          .accountsPartial({
+            bettingPool: poolPda,
+            signer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the result needs moderator votes");
+      } catch (error) {
+        expect(error.message).to.include("ModeratorVotesRequired");
+      }
+
+      try {
+        await voteResult(poolPda, user2, 1);
+        expect.fail("Should have failed - user2 is not a moderator");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedModerator");
+      }
+
+      await voteResult(poolPda, moderator, 1);
+      let bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ locked: {} });
+
+      try {
+        await voteResult(poolPda, moderator, 1);
+        expect.fail("Should have failed - moderator already voted");
+      } catch (error) {
+        expect(error.message).to.include("AlreadyVoted");
+      }
+
+      await voteResult(poolPda, user3, 1);
+      bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(1);
+    });
+
+    it("Disputes the pool when moderators disagree", async () => {
+      const poolPda = await createVotedPool("votes-conflict-" + Date.now());
+
+      await voteResult(poolPda, moderator, 1);
+      await voteResult(poolPda, user3, 2);
+
+      let bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ disputed: {} });
+
+      // The arbiter settles the deadlock; there is no bonded dispute to pass
+      await program.methods
+        .resolveDispute(2)
+        .accountsPartial({
+          config: configPda,
+          arbiter: provider.wallet.publicKey,
+          bettingPool: poolPda,
+          dispute: null,
+          challenger: null,
+          platformTreasury,
+        })
+        .rpc();
+
+      bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(2);
+    });
+  });
+
+  describe("Attested Results", () => {
+    const ATTESTED_STREAM_ID = "attested-stream-" + Date.now();
+    const attestor = anchor.web3.Keypair.generate();
+    let attestedPoolPda: PublicKey;
+    let attestedAt: anchor.BN;
+
+    function attestationMessage(poolPda: PublicKey, outcome: number, timestamp: anchor.BN): Buffer {
+      return Buffer.concat([
+        poolPda.toBuffer(),
+        Buffer.from([outcome]),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ]);
+    }
+
+    function declareWithAttestation(signer: anchor.web3.Keypair, outcome: number) {
+      return program.methods
+        .declareWinnerWithAttestation(outcome, attestedAt)
+        .accountsPartial({
+          bettingPool: attestedPoolPda,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: signer.secretKey,
+            message: attestationMessage(attestedPoolPda, outcome, attestedAt),
+          }),
+        ])
+        .rpc();
+    }
+
+    before(async () => {
+      attestedPoolPda = deriveBettingPoolPda(admin.publicKey, ATTESTED_STREAM_ID);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(ATTESTED_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: attestedPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .setAttestor(attestor.publicKey)
+        .accountsPartial({
+          bettingPool: attestedPoolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .placeBet(1, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: attestedPoolPda,
+          position: derivePositionPda(attestedPoolPda, user2.publicKey, 1),
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: attestedPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      // Sign as of the lock so the timestamp is never ahead of the validator clock
+      const bettingPool = await program.account.bettingPool.fetch(attestedPoolPda);
+      attestedAt = bettingPool.lockedAt;
+    });
+
+    it("Rejects a result signed by another key", async () => {
+      try {
+        await declareWithAttestation(user1, 1);
+        expect.fail("Should have failed - user1 is not the attestor");
+      } catch (error) {
+        expect(error.message).to.include("InvalidAttestation");
+      }
+    });
+
+    it("Cannot change the attestor once the pool is locked", async () => {
+      try {
+        await program.methods
+          .setAttestor(admin.publicKey)
+          .accountsPartial({
+            bettingPool: attestedPoolPda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the pool is locked");
+      } catch (error) {
+        expect(error.message).to.include("InvalidPoolStatus");
+      }
+    });
+
+    it("Rejects an attested result on a pool with a moderator set", async () => {
+      const streamId = "attested-moderated-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .setModerators([moderator.publicKey, user3.publicKey], 2)
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .setAttestor(attestor.publicKey)
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      const { lockedAt } = await program.account.bettingPool.fetch(poolPda);
+
+      try {
+        await program.methods
+          .declareWinnerWithAttestation(1, lockedAt)
+          .accountsPartial({
+            bettingPool: poolPda,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          })
+          .preInstructions([
+            Ed25519Program.createInstructionWithPrivateKey({
+              privateKey: attestor.secretKey,
+              message: attestationMessage(poolPda, 1, lockedAt),
+            }),
+          ])
+          .rpc();
+        expect.fail("Should have failed - the moderator set decides the result");
+      } catch (error) {
+        expect(error.message).to.include("ModeratorVotesRequired");
+      }
+    });
+
+    it("Declares a result signed by the attestor", async () => {
+      await declareWithAttestation(attestor, 1);
+
+      const bettingPool = await program.account.bettingPool.fetch(attestedPoolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(1);
+    });
+  });
+
+  describe("Oracle Resolution", () => {
+    async function createResolvedPool(streamId: string, resolver: any, prediction: number): Promise<PublicKey> {
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], resolver, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .placeBet(prediction, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: derivePositionPda(poolPda, user2.publicKey, prediction),
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      return poolPda;
+    }
+
+    function resolveFromOracle(poolPda: PublicKey, resolverAccount: PublicKey) {
+      return program.methods
+        .resolveFromOracle()
+        .accountsPartial({
+          bettingPool: poolPda,
+          resolverAccount,
+        })
+        .rpc();
+    }
+
+    it("Resolves a pool from a result account once it is published", async () => {
+      const result = anchor.web3.Keypair.generate();
+      await mockResolver.methods
+        .createResult()
+        .accountsPartial({
+          result: result.publicKey,
+          authority: provider.wallet.publicKey,
+        })
+        .signers([result])
+        .rpc();
+
+      const poolPda = await createResolvedPool(
+        "oracle-result-" + Date.now(),
+        { kind: { resultAccount: {} }, account: result.publicKey, owner: mockResolver.programId },
+        2
+      );
+
+      try {
+        await resolveFromOracle(poolPda, result.publicKey);
+        expect.fail("Should have failed - no result published yet");
+      } catch (error) {
+        expect(error.message).to.include("ResultNotAvailable");
+      }
+
+      await mockResolver.methods
+        .setResult(2)
+        .accountsPartial({
+          result: result.publicKey,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      await resolveFromOracle(poolPda, result.publicKey);
+
+      const bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(2);
+    });
+
+    it("Resolves a pool from a value feed against its threshold", async () => {
+      const feed = anchor.web3.Keypair.generate();
+      await mockResolver.methods
+        .createFeed()
+        .accountsPartial({
+          feed: feed.publicKey,
+          authority: provider.wallet.publicKey,
+        })
+        .signers([feed])
+        .rpc();
+
+      const poolPda = await createResolvedPool(
+        "oracle-feed-" + Date.now(),
+        { kind: { valueFeed: { threshold: new anchor.BN(100) } }, account: feed.publicKey, owner: mockResolver.programId },
+        1
+      );
+
+      await mockResolver.methods
+        .setValue(new anchor.BN(150))
+        .accountsPartial({
+          feed: feed.publicKey,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      await resolveFromOracle(poolPda, feed.publicKey);
+
+      const bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(1);
+    });
+  });
+
+  describe("Pool Roles", () => {
+    const ROLES_STREAM_ID = "roles-stream-" + Date.now();
+    let rolesPoolPda: PublicKey;
+
+    before(async () => {
+      rolesPoolPda = deriveBettingPoolPda(admin.publicKey, ROLES_STREAM_ID);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(ROLES_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: rolesPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Replaces the moderator", async () => {
+      try {
+        await program.methods
+          .setModerator(user1.publicKey)
+          .accountsPartial({
+            bettingPool: rolesPoolPda,
+            admin: moderator.publicKey,
+          })
+          .signers([moderator])
+          .rpc();
+        expect.fail("Should have failed - only the admin can replace the moderator");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedAdmin");
+      }
+
+      await program.methods
+        .setModerator(user3.publicKey)
+        .accountsPartial({
+          bettingPool: rolesPoolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(rolesPoolPda);
+      expect(bettingPool.moderator.toString()).to.equal(user3.publicKey.toString());
+    });
+
+    it("Hands the admin role over in two steps", async () => {
+      await program.methods
+        .proposeAdmin(user1.publicKey)
+        .accountsPartial({
+          bettingPool: rolesPoolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accountsPartial({
+            bettingPool: rolesPoolPda,
+            newAdmin: user2.publicKey,
+          })
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have failed - user2 was not proposed");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAdmin");
+      }
+
+      await program.methods
+        .acceptAdmin()
+        .accountsPartial({
+          bettingPool: rolesPoolPda,
+          newAdmin: user1.publicKey,
+        })
+        .signers([user1])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(rolesPoolPda);
+      expect(bettingPool.admin.toString()).to.equal(user1.publicKey.toString());
+      expect(bettingPool.pendingAdmin).to.be.null;
+      // The pool keeps its address, which is seeded by the creator
+      expect(bettingPool.creator.toString()).to.equal(admin.publicKey.toString());
+    });
+
+    it("Lets only the platform authority change a pool's treasury", async () => {
+      const newTreasury = anchor.web3.Keypair.generate().publicKey;
+      try {
+        await program.methods
+          .setTreasury(newTreasury)
+          .accountsPartial({
+            config: configPda,
+            authority: admin.publicKey,
+            bettingPool: rolesPoolPda,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - admin is not the platform authority");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedConfigAuthority");
+      }
+
+      await program.methods
+        .setTreasury(newTreasury)
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          bettingPool: rolesPoolPda,
+        })
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(rolesPoolPda);
+      expect(bettingPool.platformTreasury.toString()).to.equal(newTreasury.toString());
+    });
+  });
+
+  describe("Deadline Changes", () => {
+    const DEADLINE_STREAM_ID = "deadline-stream-" + Date.now();
+    let deadlinePoolPda: PublicKey;
+
+    function updateDeadline(signer: anchor.web3.Keypair, deadline: number) {
+      return program.methods
+        .updateDeadline(new anchor.BN(deadline))
+        .accountsPartial({
+          bettingPool: deadlinePoolPda,
+          config: configPda,
+          signer: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    before(async () => {
+      deadlinePoolPda = deriveBettingPoolPda(admin.publicKey, DEADLINE_STREAM_ID);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(DEADLINE_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: deadlinePoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Lets the moderator push the deadline out", async () => {
+      const newDeadline = Math.floor(Date.now() / 1000) + (2 * 60 * 60);
+      await updateDeadline(moderator, newDeadline);
+
+      const bettingPool = await program.account.bettingPool.fetch(deadlinePoolPda);
+      expect(bettingPool.bettingDeadline.toNumber()).to.equal(newDeadline);
+    });
+
+    it("Caps repeated extensions at the original deadline plus the configured maximum", async () => {
+      const bettingPool = await program.account.bettingPool.fetch(deadlinePoolPda);
+      const config = await program.account.programConfig.fetch(configPda);
+      const latestDeadline = bettingPool.originalDeadline.add(config.maxDeadlineExtension).toNumber();
+
+      await updateDeadline(admin, latestDeadline);
+      const extendedPool = await program.account.bettingPool.fetch(deadlinePoolPda);
+      expect(extendedPool.bettingDeadline.toNumber()).to.equal(latestDeadline);
+      expect(extendedPool.originalDeadline.toNumber()).to.equal(bettingPool.originalDeadline.toNumber());
+
+      try {
+        await updateDeadline(admin, latestDeadline + 60);
+        expect.fail("Should have failed - a second extension can't go past the original bound");
+      } catch (error) {
+        expect(error.message).to.include("DeadlineTooFar");
+      }
+    });
+
+    it("Fails to change the deadline as a bettor", async () => {
+      try {
+        await updateDeadline(user1, Math.floor(Date.now() / 1000) + (3 * 60 * 60));
+        expect.fail("Should have failed - only the admin or a moderator can move the deadline");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("Fails to push the deadline past the configured maximum", async () => {
+      try {
+        await updateDeadline(admin, Math.floor(Date.now() / 1000) + (2 * 24 * 60 * 60));
+        expect.fail("Should have failed - the deadline is too far out");
+      } catch (error) {
+        expect(error.message).to.include("DeadlineTooFar");
+      }
+    });
+
+    it("Fails to pull the deadline into the past", async () => {
+      try {
+        await updateDeadline(admin, Math.floor(Date.now() / 1000) - 60);
+        expect.fail("Should have failed - the deadline is in the past");
+      } catch (error) {
+        expect(error.message).to.include("InvalidDeadline");
+      }
+    });
+  });
+
+  describe("Bet Cancellation", () => {
+    const CANCEL_BET_STREAM_ID = "cancel-bet-stream-" + Date.now();
+    let cancelBetPoolPda: PublicKey;
+    let cancelBetPositionPda: PublicKey;
+
+    before(async () => {
+      cancelBetPoolPda = deriveBettingPoolPda(admin.publicKey, CANCEL_BET_STREAM_ID);
+      cancelBetPositionPda = derivePositionPda(cancelBetPoolPda, user1.publicKey, 1);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(CANCEL_BET_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Sets a 5% cancellation fee before any bets", async () => {
+      await program.methods
+        .setCancellationFee(500)
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(cancelBetPoolPda);
+      expect(bettingPool.cancellationFeeRate).to.equal(500);
+    });
+
+    it("Cancels a bet and refunds it minus the fee", async () => {
+      await program.methods
+        .placeBet(1, BET_AMOUNT_1_SOL)
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          position: cancelBetPositionPda,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+
+      try {
+        await program.methods
+          .setCancellationFee(1000)
+          .accountsPartial({
+            bettingPool: cancelBetPoolPda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the pool already has bets");
+      } catch (error) {
+        expect(error.message).to.include("BetsAlreadyPlaced");
+      }
+
+      const balanceBefore = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .cancelBet()
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          position: cancelBetPositionPda,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+      const balanceAfter = await provider.connection.getBalance(user1.publicKey);
+
+      // 0.95 SOL refunded plus the position's rent, less the transaction fee
+      expect(balanceAfter - balanceBefore).to.be.at.least(0.95 * LAMPORTS_PER_SOL);
+      expect(balanceAfter - balanceBefore).to.be.below(LAMPORTS_PER_SOL);
+      expect(await provider.connection.getAccountInfo(cancelBetPositionPda)).to.be.null;
+
+      const bettingPool = await program.account.bettingPool.fetch(cancelBetPoolPda);
+      expect(bettingPool.totalPool.toNumber()).to.equal(0);
+      expect(bettingPool.outcomes[0].totalBets.toNumber()).to.equal(0);
+      expect(bettingPool.outcomes[0].betCount).to.equal(0);
+      expect(bettingPool.outcomes[0].positionCount).to.equal(0);
+      expect(bettingPool.feesRetained.toNumber()).to.equal(0.05 * LAMPORTS_PER_SOL);
+    });
+
+    it("Pays the retained cancellation fee to the platform treasury", async () => {
+      await program.methods
+        .cancelPool()
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const treasuryBefore = await provider.connection.getBalance(platformTreasury);
+      await program.methods
+        .payoutPlatform()
+        .accountsPartial({
+          bettingPool: cancelBetPoolPda,
+          platformWallet: platformTreasury,
+          authority: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const treasuryAfter = await provider.connection.getBalance(platformTreasury);
+
+      expect(treasuryAfter - treasuryBefore).to.equal(0.05 * LAMPORTS_PER_SOL);
+      const bettingPool = await program.account.bettingPool.fetch(cancelBetPoolPda);
+      expect(bettingPool.platformFeePaid.toNumber()).to.equal(0);
+      expect(bettingPool.feesRetainedPaid.toNumber()).to.equal(0.05 * LAMPORTS_PER_SOL);
+    });
+  });
+
+  describe("Fixed Odds", () => {
+    const FIXED_ODDS_STREAM_ID = "fixed-odds-stream-" + Date.now();
+    let fixedOddsPoolPda: PublicKey;
+    let fixedOddsPositionPda: PublicKey;
+
+    function placeBet(user: anchor.web3.Keypair, prediction: number) {
+      return program.methods
+        .placeBet(prediction, BET_AMOUNT_1_SOL)
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          position: derivePositionPda(fixedOddsPoolPda, user.publicKey, prediction),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    }
+
+    function setOdds(odds: number[]) {
+      return program.methods
+        .setOdds(odds)
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+    }
+
+    function withdrawLiquidity() {
+      return program.methods
+        .withdrawLiquidity()
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    }
+
+    before(async () => {
+      fixedOddsPoolPda = deriveBettingPoolPda(admin.publicKey, FIXED_ODDS_STREAM_ID);
+      fixedOddsPositionPda = derivePositionPda(fixedOddsPoolPda, user1.publicKey, 1);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(FIXED_ODDS_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { fixedOdds: {} })
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Rejects bets until odds are set", async () => {
+      try {
+        await placeBet(user1, 1);
+        expect.fail("Should have failed - no odds set");
+      } catch (error) {
+        expect(error.message).to.include("InvalidOdds");
+      }
+    });
+
+    it("Records the odds each bet was placed at, capped by the liquidity", async () => {
+      await setOdds([19000, 25000]);
+      await program.methods
+        .depositLiquidity(BET_AMOUNT_1_SOL)
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await placeBet(user1, 1);
+      const position = await program.account.position.fetch(fixedOddsPositionPda);
+      expect(position.potentialPayout.toNumber()).to.equal(1.9 * LAMPORTS_PER_SOL);
+
+      // Another 1.9 SOL owed on Player 1 is more than 2 SOL staked plus 1 SOL liquidity can cover after fees
+      try {
+        await placeBet(user1, 1);
+        expect.fail("Should have failed - the liquidity cannot cover the bet");
+      } catch (error) {
+        expect(error.message).to.include("LiquidityExceeded");
+      }
+
+      await setOdds([15000, 22000]);
+      await placeBet(user2, 2);
+      const user2Position = await program.account.position.fetch(derivePositionPda(fixedOddsPoolPda, user2.publicKey, 2));
+      expect(user2Position.potentialPayout.toNumber()).to.equal(2.2 * LAMPORTS_PER_SOL);
+
+      const bettingPool = await program.account.bettingPool.fetch(fixedOddsPoolPda);
+      expect(bettingPool.liquidity.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[0].liability.toNumber()).to.equal(1.9 * LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].liability.toNumber()).to.equal(2.2 * LAMPORTS_PER_SOL);
+    });
+
+    it("Pays winners their odds and returns the rest to the admin", async () => {
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .declareWinner(1)
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const winnerBalanceBefore = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .payoutWinners()
+        .accountsPartial({
+          bettingPool: fixedOddsPoolPda,
+          position: fixedOddsPositionPda,
+          winner: user1.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const winnerBalanceAfter = await provider.connection.getBalance(user1.publicKey);
+      expect(winnerBalanceAfter - winnerBalanceBefore).to.equal(1.9 * LAMPORTS_PER_SOL);
+
+      // 2 SOL staked plus 1 SOL liquidity, less 1.9 SOL paid out and the 0.05 SOL platform fee
+      await withdrawLiquidity();
+      const bettingPool = await program.account.bettingPool.fetch(fixedOddsPoolPda);
+      expect(bettingPool.liquidityReturned.toNumber()).to.equal(1.05 * LAMPORTS_PER_SOL);
+
+      try {
+        await withdrawLiquidity();
+        expect.fail("Should have failed - the liquidity was already returned");
+      } catch (error) {
+        expect(error.message).to.include("LiquidityAlreadyReturned");
+      }
+    });
+  });
+
+  describe("LMSR Market", () => {
+    const LMSR_STREAM_ID = "lmsr-stream-" + Date.now();
+    let lmsrPoolPda: PublicKey;
+    let lmsrPositionPda: PublicKey;
+
+    function buyShares(user: anchor.web3.Keypair, outcome: number, shares: anchor.BN, maxCost: anchor.BN) {
+      return program.methods
+        .buyShares(outcome, shares, maxCost)
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          position: derivePositionPda(lmsrPoolPda, user.publicKey, outcome),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    }
+
+    before(async () => {
+      lmsrPoolPda = deriveBettingPoolPda(admin.publicKey, LMSR_STREAM_ID);
+      lmsrPositionPda = derivePositionPda(lmsrPoolPda, user1.publicKey, 1);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(LMSR_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { lmsr: {} })
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("Rejects trades until the market is seeded", async () => {
+      try {
+        await buyShares(user1, 1, BET_AMOUNT_1_SOL, BET_AMOUNT_1_SOL);
+        expect.fail("Should have failed - the market is not seeded");
+      } catch (error) {
+        expect(error.message).to.include("MarketNotSeeded");
+      }
+    });
+
+    it("Seeds the market with b * ln(n)", async () => {
+      await program.methods
+        .seedMarket(BET_AMOUNT_1_SOL)
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(lmsrPoolPda);
+      expect(bettingPool.liquidityParameter.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      // 1 SOL * ln(2), rounded up
+      expect(bettingPool.liquidity.toNumber()).to.equal(693_147_181);
+    });
+
+    it("Buys shares at the market price within the slippage limit", async () => {
+      // 1 SOL of shares from even prices costs ln(1 + e) - ln(2) = 0.62011 SOL
+      try {
+        await buyShares(user1, 1, BET_AMOUNT_1_SOL, new anchor.BN(0.6 * LAMPORTS_PER_SOL));
+        expect.fail("Should have failed - the cost is above the maximum");
+      } catch (error) {
+        expect(error.message).to.include("SlippageExceeded");
+      }
+      await buyShares(user1, 1, BET_AMOUNT_1_SOL, new anchor.BN(0.63 * LAMPORTS_PER_SOL));
+
+      const position = await program.account.position.fetch(lmsrPositionPda);
+      expect(position.potentialPayout.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(position.amount.toNumber()).to.equal(620_114_507);
+
+      // Player 2 is now the cheaper side
+      await buyShares(user2, 2, BET_AMOUNT_1_SOL, new anchor.BN(0.5 * LAMPORTS_PER_SOL));
+      const bettingPool = await program.account.bettingPool.fetch(lmsrPoolPda);
+      expect(bettingPool.outcomes[0].liability.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(bettingPool.outcomes[1].liability.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    });
+
+    it("Sells shares back to the market", async () => {
+      const balanceBefore = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .sellShares(new anchor.BN(0.5 * LAMPORTS_PER_SOL), new anchor.BN(0.2 * LAMPORTS_PER_SOL))
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          position: lmsrPositionPda,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+      const balanceAfter = await provider.connection.getBalance(user1.publicKey);
+      // Half a SOL of shares at prices around one half, less the transaction fee
+      expect(balanceAfter - balanceBefore).to.be.above(0.2 * LAMPORTS_PER_SOL);
+      expect(balanceAfter - balanceBefore).to.be.below(0.5 * LAMPORTS_PER_SOL);
+
+      const position = await program.account.position.fetch(lmsrPositionPda);
+      expect(position.potentialPayout.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+      expect(position.amount.toNumber()).to.equal(310_057_254);
+    });
+
+    it("Redeems each winning share for one lamport", async () => {
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .declareWinner(1)
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const winnerBalanceBefore = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .payoutWinners()
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          position: lmsrPositionPda,
+          winner: user1.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const winnerBalanceAfter = await provider.connection.getBalance(user1.publicKey);
+      expect(winnerBalanceAfter - winnerBalanceBefore).to.equal(0.5 * LAMPORTS_PER_SOL);
+
+      await program.methods
+        .withdrawLiquidity()
+        .accountsPartial({
+          bettingPool: lmsrPoolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const bettingPool = await program.account.bettingPool.fetch(lmsrPoolPda);
+      expect(bettingPool.liquidityReturned.toNumber()).to.be.above(0);
+      expect(bettingPool.status).to.deep.equal({ settled: {} });
+    });
+  });
+
+  describe("Token Pools", () => {
+    const TOKEN_STREAM_ID = "token-stream-" + Date.now();
+    const TOKEN_BET_AMOUNT = new anchor.BN(1_000_000); // 1 token with 6 decimals
+    let mint: PublicKey;
+    let tokenPoolPda: PublicKey;
+    let tokenVaultPda: PublicKey;
+    let tokenPositionPda: PublicKey;
+    let user1TokenAccount: PublicKey;
+    let user2TokenAccount: PublicKey;
+    let adminTokenAccount: PublicKey;
+    let treasuryTokenAccount: PublicKey;
+
+    async function tokenBalance(account: PublicKey): Promise<number> {
+      return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+      mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      user1TokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, user1.publicKey)
+      ).address;
+      user2TokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, user2.publicKey)
+      ).address;
+      adminTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)
+      ).address;
+      treasuryTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, platformTreasury)
+      ).address;
+      await mintTo(provider.connection, admin, mint, user1TokenAccount, admin, 10_000_000);
+      await mintTo(provider.connection, admin, mint, user2TokenAccount, admin, 10_000_000);
+
+      tokenPoolPda = deriveBettingPoolPda(admin.publicKey, TOKEN_STREAM_ID);
+
+      [tokenVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_vault"), tokenPoolPda.toBuffer()],
+        program.programId
+      );
+
+      tokenPositionPda = derivePositionPda(tokenPoolPda, user1.publicKey, 1);
+    });
+
+    it("Creates a token pool with its vault", async () => {
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(TOKEN_STREAM_ID, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          admin: admin.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const bettingPool = await program.account.bettingPool.fetch(tokenPoolPda);
+      expect(bettingPool.mint.toString()).to.equal(mint.toString());
+
+      const vault = await getAccount(provider.connection, tokenVaultPda);
+      expect(vault.owner.toString()).to.equal(tokenPoolPda.toString());
+    });
+
+    it("User1 bets tokens on Player 1", async () => {
+      await program.methods
+        .placeBet(1, TOKEN_BET_AMOUNT)
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          position: tokenPositionPda,
+          user: user1.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          userTokenAccount: user1TokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+
+      const vault = await getAccount(provider.connection, tokenVaultPda);
+      expect(Number(vault.amount)).to.equal(TOKEN_BET_AMOUNT.toNumber());
+
+      const bettingPool = await program.account.bettingPool.fetch(tokenPoolPda);
+      expect(bettingPool.totalPool.toNumber()).to.equal(TOKEN_BET_AMOUNT.toNumber());
+    });
+
+    it("Fails to bet on a token pool without token accounts", async () => {
+      const positionPda = derivePositionPda(tokenPoolPda, user1.publicKey, 1);
+
+      try {
+        await program.methods
+          .placeBet(1, TOKEN_BET_AMOUNT)
+          .accountsPartial({
+            bettingPool: tokenPoolPda,
+            position: positionPda,
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - token accounts are required");
+      } catch (error) {
+        expect(error.message).to.include("MissingTokenAccounts");
+      }
+    });
+
+    it("Pays out the winner and fees in tokens and closes the pool", async () => {
+      const user2PositionPda = derivePositionPda(tokenPoolPda, user2.publicKey, 2);
+      await program.methods
+        .placeBet(2, TOKEN_BET_AMOUNT)
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          position: user2PositionPda,
+          user: user2.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          userTokenAccount: user2TokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .declareWinner(1)
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const user1Before = await tokenBalance(user1TokenAccount);
+      const adminBefore = await tokenBalance(adminTokenAccount);
+      const treasuryBefore = await tokenBalance(treasuryTokenAccount);
+      await program.methods
+        .payoutWinners()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          position: tokenPositionPda,
+          winner: user1.publicKey,
+          payer: admin.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          winnerTokenAccount: user1TokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .payoutCreator()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          admin: admin.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          adminTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .payoutPlatform()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          platformWallet: platformTreasury,
+          authority: admin.publicKey,
+          mint,
+          tokenVault: tokenVaultPda,
+          platformWalletTokenAccount: treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      // 2 tokens staked, 2.5% each to the creator and the platform, the rest to the winner
+      expect((await tokenBalance(user1TokenAccount)) - user1Before).to.equal(1_900_000);
+      expect((await tokenBalance(adminTokenAccount)) - adminBefore).to.equal(50_000);
+      expect((await tokenBalance(treasuryTokenAccount)) - treasuryBefore).to.equal(50_000);
+      expect(await tokenBalance(tokenVaultPda)).to.equal(0);
+
+      await program.methods
+        .closeLosingBet()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          position: user2PositionPda,
+          user: user2.publicKey,
+          payer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .closePool()
+        .accountsPartial({
+          bettingPool: tokenPoolPda,
+          admin: admin.publicKey,
+          platformTreasury,
+          mint,
+          tokenVault: tokenVaultPda,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(tokenVaultPda)).to.be.null;
+      expect(await provider.connection.getAccountInfo(tokenPoolPda)).to.be.null;
+    });
+
+    it("Refunds tokens from a cancelled pool", async () => {
+      const streamId = "token-refund-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_vault"), poolPda.toBuffer()],
+        program.programId
+      );
+      const positionPda = derivePositionPda(poolPda, user1.publicKey, 2);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          mint,
+          tokenVault: vaultPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .placeBet(2, TOKEN_BET_AMOUNT)
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: positionPda,
+          user: user1.publicKey,
+          mint,
+          tokenVault: vaultPda,
+          userTokenAccount: user1TokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+      await program.methods
+        .cancelPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const balanceBefore = await tokenBalance(user1TokenAccount);
+      await program.methods
+        .claimRefund()
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: positionPda,
+          user: user1.publicKey,
+          payer: admin.publicKey,
+          mint,
+          tokenVault: vaultPda,
+          userTokenAccount: user1TokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      expect((await tokenBalance(user1TokenAccount)) - balanceBefore).to.equal(TOKEN_BET_AMOUNT.toNumber());
+      expect(await provider.connection.getAccountInfo(positionPda)).to.be.null;
+    });
+  });
+
+  describe("Token-2022 Transfer Fees", () => {
+    const TRANSFER_FEE_BPS = 100; // 1% on every transfer
+    let feeMint: PublicKey;
+
+    function deriveTokenVaultPda(poolPda: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("token_vault"), poolPda.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    before(async () => {
+      const mintKeypair = anchor.web3.Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mintKeypair.publicKey,
+          admin.publicKey,
+          admin.publicKey,
+          TRANSFER_FEE_BPS,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 6, admin.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await provider.sendAndConfirm(tx, [admin, mintKeypair]);
+      feeMint = mintKeypair.publicKey;
+    });
+
+    it("Rejects an LMSR pool on a mint with a transfer fee", async () => {
+      const streamId = "fee-lmsr-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+
+      try {
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { lmsr: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            mint: feeMint,
+            tokenVault: deriveTokenVaultPda(poolPda),
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - LMSR shares cannot pay a transfer fee");
+      } catch (error) {
+        expect(error.message).to.include("TransferFeeNotSupported");
+      }
+    });
+
+    it("Settles and closes a parimutuel pool on a mint with a transfer fee", async () => {
+      const tokenAccount = async (owner: PublicKey) =>
+        (
+          await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            admin,
+            feeMint,
+            owner,
+            false,
+            undefined,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ).address;
+      const tokenBalance = async (account: PublicKey) =>
+        Number((await getAccount(provider.connection, account, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+
+      const user1TokenAccount = await tokenAccount(user1.publicKey);
+      const user2TokenAccount = await tokenAccount(user2.publicKey);
+      const adminTokenAccount = await tokenAccount(admin.publicKey);
+      const treasuryTokenAccount = await tokenAccount(platformTreasury);
+      for (const account of [user1TokenAccount, user2TokenAccount]) {
+        await mintTo(provider.connection, admin, feeMint, account, admin, 10_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+      }
+
+      const streamId = "fee-mint-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const vaultPda = deriveTokenVaultPda(poolPda);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          mint: feeMint,
+          tokenVault: vaultPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const tokenAccounts = { mint: feeMint, tokenVault: vaultPda, tokenProgram: TOKEN_2022_PROGRAM_ID };
+      for (const [bettor, account, prediction] of [[user1, user1TokenAccount, 1], [user2, user2TokenAccount, 2]] as const) {
+        await program.methods
+          .placeBet(prediction, new anchor.BN(1_000_000))
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: derivePositionPda(poolPda, bettor.publicKey, prediction),
+            user: bettor.publicKey,
+            userTokenAccount: account,
+            systemProgram: SystemProgram.programId,
+            ...tokenAccounts,
+          })
+          .signers([bettor])
+          .rpc();
+      }
+
+      // Each stake is what arrived after the 1% fee withheld in the vault
+      let bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.totalPool.toNumber()).to.equal(1_980_000);
+
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .declareWinner(1)
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      await program.methods
+        .payoutWinners()
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: derivePositionPda(poolPda, user1.publicKey, 1),
+          winner: user1.publicKey,
+          payer: admin.publicKey,
+          winnerTokenAccount: user1TokenAccount,
+          systemProgram: SystemProgram.programId,
+          ...tokenAccounts,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .payoutCreator()
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          adminTokenAccount,
+          systemProgram: SystemProgram.programId,
+          ...tokenAccounts,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .payoutPlatform()
+        .accountsPartial({
+          bettingPool: poolPda,
+          platformWallet: platformTreasury,
+          authority: admin.publicKey,
+          platformWalletTokenAccount: treasuryTokenAccount,
+          systemProgram: SystemProgram.programId,
+          ...tokenAccounts,
+        })
+        .signers([admin])
+        .rpc();
+
+      bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ settled: {} });
+      expect(await tokenBalance(vaultPda)).to.equal(0);
+
+      await program.methods
+        .closeLosingBet()
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: derivePositionPda(poolPda, user2.publicKey, 2),
+          user: user2.publicKey,
+          payer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const withheldBefore = getTransferFeeConfig(
+        await getMint(provider.connection, feeMint, undefined, TOKEN_2022_PROGRAM_ID)
+      ).withheldAmount;
+      await program.methods
+        .closePool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          platformTreasury,
+          treasuryTokenAccount,
+          systemProgram: SystemProgram.programId,
+          ...tokenAccounts,
+        })
+        .signers([admin])
+        .rpc();
+
+      // The fees withheld on both deposits were harvested to the mint so the vault could close
+      expect(await provider.connection.getAccountInfo(vaultPda)).to.be.null;
+      const withheldAfter = getTransferFeeConfig(
+        await getMint(provider.connection, feeMint, undefined, TOKEN_2022_PROGRAM_ID)
+      ).withheldAmount;
+      expect(Number(withheldAfter) - Number(withheldBefore)).to.equal(20_000);
+    });
+  });
+
+  describe("Legacy Pools", () => {
+    // Preloaded from tests/fixtures by Anchor.toml: a pool on "legacy-stream" created before
+    // creator namespacing, with 1 SOL and 2 SOL on player 1, 1 SOL on player 2 and 2.5% fees
+    const LEGACY_STREAM_ID = "legacy-stream";
+    const LEGACY_BETTORS = [
+      new PublicKey("5djXsZUNampfUfTiTYGK5ZmhuYoE9YciDj3PXsYCavMW"),
+      new PublicKey("De38YaUKC1dGSFQn3f9pNhMwQzpPbXiefrtKqbz3mmPV"),
+      new PublicKey("DgrtiRA6mhW3ajqoawZhYaxuuysac2q7MqRqqSFL6WU6"),
+    ];
+    let legacyPoolPda: PublicKey;
+    let legacyAdmin: anchor.web3.Keypair;
+
+    before(async function () {
+      legacyPoolPda = deriveLegacyBettingPoolPda(LEGACY_STREAM_ID);
+      // The fixtures are only loaded when `anchor test` runs its own validator
+      if (!(await provider.connection.getAccountInfo(legacyPoolPda))) {
+        this.skip();
+      }
+      const adminJson = JSON.parse(fs.readFileSync(path.join(__dirname, "fixtures", "legacy-admin.json"), "utf-8"));
+      legacyAdmin = anchor.web3.Keypair.fromSecretKey(Buffer.from(adminJson));
+    });
+
+    it("Derives both pool forms for a stream", async () => {
+      expect(legacyPoolPda.equals(deriveBettingPoolPda(legacyAdmin.publicKey, LEGACY_STREAM_ID))).to.be.false;
+
+      const legacyPool = await program.account.legacyBettingPool.fetch(legacyPoolPda);
+      expect(legacyPool.streamId).to.equal(LEGACY_STREAM_ID);
+      expect(legacyPool.admin.equals(legacyAdmin.publicKey)).to.be.true;
+      expect(legacyPool.totalPool.toNumber()).to.equal(4 * LAMPORTS_PER_SOL);
+
+      const legacyBet = await program.account.legacyBet.fetch(deriveLegacyBetPda(legacyPoolPda, LEGACY_BETTORS[2], 2));
+      expect(legacyBet.bettingPool.equals(legacyPoolPda)).to.be.true;
+      expect(legacyBet.amount.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+    });
+
+    it("Fails to place a bet on a legacy pool", async () => {
+      try {
+        await program.methods
+          .placeBet(1, BET_AMOUNT_1_SOL)
+          .accountsPartial({
+            bettingPool: legacyPoolPda,
+            position: derivePositionPda(legacyPoolPda, user1.publicKey, 1),
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - legacy pools take no new bets");
+      } catch (error) {
+        expect(error.message).to.not.include("Should have failed");
+      }
+      expect(await provider.connection.getAccountInfo(derivePositionPda(legacyPoolPda, user1.publicKey, 1))).to.be.null;
+    });
+
+    it("Fails to close a legacy pool before it settles", async () => {
+      try {
+        await program.methods
+          .closeLegacyPool()
+          .accountsPartial({
+            bettingPool: legacyPoolPda,
+            config: configPda,
+            admin: legacyAdmin.publicKey,
+            platformTreasury: (await program.account.legacyBettingPool.fetch(legacyPoolPda)).platformTreasury,
+          })
+          .signers([legacyAdmin])
+          .rpc();
+        expect.fail("Should have failed - no result yet");
+      } catch (error) {
+        expect(error.message).to.include("SettlementIncomplete");
+      }
+    });
+
+    it("Fails to declare a legacy result as a bettor", async () => {
+      try {
+        await program.methods
+          .declareLegacyResult(1)
+          .accountsPartial({
+            bettingPool: legacyPoolPda,
+            config: configPda,
+            signer: user1.publicKey,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - only the admin or moderator declares the result");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("Declares a legacy pool's result", async () => {
+      await program.methods
+        .declareLegacyResult(1)
+        .accountsPartial({
+          bettingPool: legacyPoolPda,
+          config: configPda,
+          signer: legacyAdmin.publicKey,
+        })
+        .signers([legacyAdmin])
+        .rpc();
+
+      const legacyPool = await program.account.legacyBettingPool.fetch(legacyPoolPda);
+      expect(legacyPool.winnerDeclared).to.be.true;
+      expect(legacyPool.winningOutcome).to.equal(1);
+      expect(legacyPool.isPayoutComplete).to.be.false;
+    });
+
+    it("Settles every legacy bet to its owner", async () => {
+      // 3.8 SOL prize pool split over 3 SOL on player 1; the loser gets back its bet's rent
+      const expectedPayouts = [1_266_666_666, 0, 2_533_333_333];
+
+      for (const [index, bettor] of LEGACY_BETTORS.entries()) {
+        const betPda = deriveLegacyBetPda(legacyPoolPda, bettor, index);
+        const betRent = (await provider.connection.getAccountInfo(betPda)).lamports;
+        const balanceBefore = await provider.connection.getBalance(bettor);
+
+        await program.methods
+          .settleLegacyBet()
+          .accountsPartial({
+            bettingPool: legacyPoolPda,
+            config: configPda,
+            bet: betPda,
+            user: bettor,
+            payer: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        const balanceAfter = await provider.connection.getBalance(bettor);
+        expect(balanceAfter - balanceBefore).to.equal(expectedPayouts[index] + betRent);
+        expect(await provider.connection.getAccountInfo(betPda)).to.be.null;
+      }
+
+      const legacyPool = await program.account.legacyBettingPool.fetch(legacyPoolPda);
+      expect(legacyPool.player1BetCount).to.equal(0);
+      expect(legacyPool.player2BetCount).to.equal(0);
+      expect(legacyPool.isPayoutComplete).to.be.true;
+    });
+
+    it("Closes a settled legacy pool, paying the creator and platform fees", async () => {
+      const legacyPool = await program.account.legacyBettingPool.fetch(legacyPoolPda);
+      const poolRent = await provider.connection.getMinimumBalanceForRentExemption(
+        (await provider.connection.getAccountInfo(legacyPoolPda)).data.length
+      );
+      const adminBefore = await provider.connection.getBalance(legacyAdmin.publicKey);
+      const treasuryBefore = await provider.connection.getBalance(legacyPool.platformTreasury);
+
+      await program.methods
+        .closeLegacyPool()
+        .accountsPartial({
+          bettingPool: legacyPoolPda,
+          config: configPda,
+          admin: legacyAdmin.publicKey,
+          platformTreasury: legacyPool.platformTreasury,
+        })
+        .signers([legacyAdmin])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(legacyPoolPda)).to.be.null;
+      const adminAfter = await provider.connection.getBalance(legacyAdmin.publicKey);
+      const treasuryAfter = await provider.connection.getBalance(legacyPool.platformTreasury);
+      // 2.5% of 4 SOL each, the platform also gets the lamport of rounding dust
+      expect(adminAfter - adminBefore).to.equal(0.1 * LAMPORTS_PER_SOL + poolRent);
+      expect(treasuryAfter - treasuryBefore).to.equal(0.1 * LAMPORTS_PER_SOL + 1);
+    });
+  });
+
+  describe("Program Config", () => {
+    async function currentConfigParams() {
+      const config = await program.account.programConfig.fetch(configPda);
+      return {
+        authority: config.authority,
+        platformTreasury: config.platformTreasury,
+        platformFeeRate: config.platformFeeRate,
+        defaultCreatorFeeRate: config.defaultCreatorFeeRate,
+        maxCreatorFeeRate: config.maxCreatorFeeRate,
+        minBet: config.minBet,
+        maxBet: config.maxBet,
+        paused: config.paused,
+        minResolutionDelay: config.minResolutionDelay,
+        disputeWindow: config.disputeWindow,
+        disputeBond: config.disputeBond,
+        arbiter: config.arbiter,
+        cancellationCutoff: config.cancellationCutoff,
+        guardian: config.guardian,
+        maxDeadlineExtension: config.maxDeadlineExtension,
+      };
+    }
+
+    it("Fails to update config with a non-authority signer", async () => {
+      try {
+        await program.methods
+          .updateConfig(await currentConfigParams())
+          .accountsPartial({
+            config: configPda,
+            authority: user1.publicKey,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed with unauthorized config authority");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedConfigAuthority");
+      }
+    });
+
+    it("Fails to set a default creator fee above the maximum", async () => {
+      const params = await currentConfigParams();
+      try {
+        await program.methods
+          .updateConfig({ ...params, defaultCreatorFeeRate: params.maxCreatorFeeRate + 1 })
+          .accountsPartial({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+        expect.fail("Should have failed with invalid fee rate");
+      } catch (error) {
+        expect(error.message).to.include("InvalidFeeRate");
+      }
+    });
+
+    it("Enforces the minimum delay between lock and result", async () => {
+      const params = await currentConfigParams();
+      await program.methods
+        .updateConfig({ ...params, minResolutionDelay: new anchor.BN(60 * 60) })
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      try {
+        const streamId = "delayed-stream-" + Date.now();
+        const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+        const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        await program.methods
+          .lockPool()
+          .accountsPartial({
+            bettingPool: poolPda,
+            signer: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+        try {
+          await program.methods
+            .declareWinner(0)
+            .accountsPartial({
+              bettingPool: poolPda,
+              signer: admin.publicKey,
+            })
+            .signers([admin])
+            .rpc();
+          expect.fail("Should have failed - the resolution delay has not passed");
+        } catch (error) {
+          expect(error.message).to.include("ResolutionTooEarly");
+        }
+      } finally {
+        await program.methods
+          .updateConfig(params)
+          .accountsPartial({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      }
+    });
+
+    it("Lets a bettor dispute a result and the arbiter overturn it", async () => {
+      const params = await currentConfigParams();
+      await program.methods
+        .updateConfig({ ...params, disputeWindow: new anchor.BN(60 * 60) })
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const streamId = "disputed-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      try {
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+      } finally {
+        await program.methods
+          .updateConfig(params)
+          .accountsPartial({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      }
+
+      const amount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+      for (const [bettor, prediction] of [[user1, 1], [user2, 2]] as const) {
+        await program.methods
+          .placeBet(prediction, amount)
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: derivePositionPda(poolPda, bettor.publicKey, prediction),
+            user: bettor.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([bettor])
+          .rpc();
+      }
+      await program.methods
+        .lockPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .declareWinner(2)
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const user1Position = derivePositionPda(poolPda, user1.publicKey, 1);
+      const user2Position = derivePositionPda(poolPda, user2.publicKey, 2);
+      try {
+        await program.methods
+          .payoutWinners()
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: user2Position,
+            winner: user2.publicKey,
+            payer: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - the dispute window is still open");
+      } catch (error) {
+        expect(error.message).to.include("DisputeWindowOpen");
+      }
+
+      const [disputePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), poolPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .disputeResult(1)
+        .accountsPartial({
+          bettingPool: poolPda,
+          dispute: disputePda,
+          position: user1Position,
+          challenger: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+
+      let bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ disputed: {} });
+      const dispute = await program.account.dispute.fetch(disputePda);
+      expect(dispute.bond.toNumber()).to.equal(params.disputeBond.toNumber());
+      expect(dispute.proposedOutcome).to.equal(1);
+
+      const balanceBeforeRuling = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .resolveDispute(1)
+        .accountsPartial({
+          config: configPda,
+          arbiter: provider.wallet.publicKey,
+          bettingPool: poolPda,
+          dispute: disputePda,
+          challenger: user1.publicKey,
+          platformTreasury,
+        })
+        .rpc();
+
+      // An upheld challenge gets its bond back along with the dispute account's rent
+      const balanceAfterRuling = await provider.connection.getBalance(user1.publicKey);
+      expect(balanceAfterRuling - balanceBeforeRuling).to.be.at.least(params.disputeBond.toNumber());
+      expect(await provider.connection.getAccountInfo(disputePda)).to.be.null;
+
+      bettingPool = await program.account.bettingPool.fetch(poolPda);
+      expect(bettingPool.status).to.deep.equal({ resolved: {} });
+      expect(bettingPool.winningOutcome).to.equal(1);
+
+      await program.methods
+        .payoutWinners()
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: user1Position,
+          winner: user1.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      expect(await provider.connection.getAccountInfo(user1Position)).to.be.null;
+    });
+
+    it("Guardian pause blocks bets but keeps refunds of cancelled pools open", async () => {
+      const streamId = "guarded-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const positionPda = derivePositionPda(poolPda, user1.publicKey, 1);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const placeBet = () =>
+        program.methods
+          .placeBet(1, BET_AMOUNT_HALF_SOL)
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: positionPda,
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+      await placeBet();
+
+      try {
+        await program.methods
+          .setPoolPaused(true)
+          .accountsPartial({
+            config: configPda,
+            guardian: admin.publicKey,
+            bettingPool: poolPda,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - admin is not the guardian");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedGuardian");
+      }
+
+      await program.methods
+        .setPoolPaused(true)
+        .accountsPartial({
+          config: configPda,
+          guardian: provider.wallet.publicKey,
+          bettingPool: poolPda,
+        })
+        .rpc();
+
+      try {
+        await placeBet();
+        expect.fail("Should have failed - the pool is paused");
+      } catch (error) {
+        expect(error.message).to.include("PoolPaused");
+      }
+
+      await program.methods
+        .cancelPool()
+        .accountsPartial({
+          bettingPool: poolPda,
+          signer: moderator.publicKey,
+        })
+        .signers([moderator])
+        .rpc();
+      await program.methods
+        .claimRefund()
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: positionPda,
+          user: user1.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(positionPda)).to.be.null;
+    });
+
+    it("Guardian pause blocks bets across the program", async () => {
+      const streamId = "global-pause-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      await program.methods
+        .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+        .accountsPartial({
+          bettingPool: poolPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const setPaused = (paused: boolean) =>
+        program.methods
+          .setPaused(paused)
+          .accountsPartial({
+            config: configPda,
+            guardian: provider.wallet.publicKey,
+          })
+          .rpc();
+
+      await setPaused(true);
+      try {
+        await program.methods
+          .placeBet(1, BET_AMOUNT_HALF_SOL)
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: derivePositionPda(poolPda, user1.publicKey, 1),
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - the program is paused");
+      } catch (error) {
+        expect(error.message).to.include("ProgramPaused");
+      } finally {
+        await setPaused(false);
+      }
+    });
+
+    it("Stops cancellations inside the cutoff before the deadline", async () => {
+      const params = await currentConfigParams();
+      await program.methods
+        .updateConfig({ ...params, cancellationCutoff: new anchor.BN(2 * 60 * 60) })
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const streamId = "cutoff-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const positionPda = derivePositionPda(poolPda, user1.publicKey, 2);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+      try {
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+      } finally {
+        await program.methods
+          .updateConfig(params)
+          .accountsPartial({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      }
+
+      await program.methods
+        .placeBet(2, BET_AMOUNT_HALF_SOL)
+        .accountsPartial({
+          bettingPool: poolPda,
+          position: positionPda,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+
+      try {
+        await program.methods
+          .cancelBet()
+          .accountsPartial({
+            bettingPool: poolPda,
+            position: positionPda,
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have failed - the deadline is inside the cancellation cutoff");
+      } catch (error) {
+        expect(error.message).to.include("CancellationClosed");
+      }
+    });
+
+    it("Pausing the program blocks new pools", async () => {
+      const params = await currentConfigParams();
+      await program.methods
+        .updateConfig({ ...params, paused: true })
+        .accountsPartial({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const streamId = "paused-stream-" + Date.now();
+      const poolPda = deriveBettingPoolPda(admin.publicKey, streamId);
+      const bettingDeadline = Math.floor(Date.now() / 1000) + (60 * 60);
+
+      try {
+        await program.methods
+          .initialize(streamId, MATCH_NUMBER, new anchor.BN(bettingDeadline), moderator.publicKey, null, { noFees: {} }, ["Player 1", "Player 2"], null, { parimutuel: {} })
+          .accountsPartial({
+            bettingPool: poolPda,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have failed - program is paused");
+      } catch (error) {
+        expect(error.message).to.include("ProgramPaused");
+      } finally {
+        await program.methods
+          .updateConfig(params)
+          .accountsPartial({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      }
+    });
+  });
 });